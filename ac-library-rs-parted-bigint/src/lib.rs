@@ -0,0 +1,940 @@
+//! Arbitrary-precision integers.
+//!
+//! [`BigUint`] and [`BigInt`] store the values in limbs of $10^8$, so parsing and formatting in base 10 take linear
+//! time. Large values are multiplied with [`convolution_i64`] in _O(n log n)_, and divided with the schoolbook long
+//! division in _O(nm)_.
+//!
+//! # Example
+//!
+//! ```
+//! use ac_library_rs::{BigInt, BigUint};
+//!
+//! let a = "123456789012345678901234567890".parse::<BigUint>().unwrap();
+//! let b = "987654321098765432109876543210".parse::<BigUint>().unwrap();
+//! assert_eq!(
+//!     "121932631137021795226185032733622923332237463801111263526900",
+//!     (&a * &b).to_string(),
+//! );
+//! assert_eq!(
+//!     (BigUint::from(8u32), BigUint::from(9_000_000_000_900_000_000_090u128)),
+//!     b.div_rem(&a),
+//! );
+//!
+//! let c = BigInt::from(a) - BigInt::from(b);
+//! assert_eq!("-864197532086419753208641975320", c.to_string());
+//! ```
+//!
+//! [`BigUint`]: ./struct.BigUint.html
+//! [`BigInt`]: ./struct.BigInt.html
+//! [`convolution_i64`]: ../acl_convolution/fn.convolution_i64.html
+
+extern crate __acl_convolution as convolution;
+
+pub use self::bigint::*;
+
+mod bigint {
+    use super::convolution;
+    use std::{
+        cmp::{self, Ordering},
+        fmt::{self, Write as _},
+        iter,
+        ops::{
+            Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+        },
+        str::FromStr,
+    };
+
+    const BASE: u32 = 100_000_000;
+    const BASE_DIGITS: usize = 8;
+
+    /// An arbitrary-precision unsigned integer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::BigUint;
+    ///
+    /// let a = BigUint::from(2u32).pow(100);
+    /// assert_eq!("1267650600228229401496703205376", a.to_string());
+    /// assert_eq!(3_205_376, a.div_rem_u32(10_000_000).1);
+    /// ```
+    #[derive(Clone, PartialEq, Eq, Hash, Default)]
+    pub struct BigUint {
+        /// The little-endian limbs less than `BASE` without trailing zeros.
+        limbs: Vec<u32>,
+    }
+
+    impl BigUint {
+        /// Returns `true` if `self` is zero.
+        #[inline]
+        pub fn is_zero(&self) -> bool {
+            self.limbs.is_empty()
+        }
+
+        /// Returns `self - rhs`, or `None` if `self < rhs`.
+        pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+            if self < rhs {
+                return None;
+            }
+            let mut limbs = self.limbs.clone();
+            let mut borrow = 0;
+            for (i, l) in limbs.iter_mut().enumerate() {
+                let r = rhs.limbs.get(i).copied().unwrap_or(0) + borrow;
+                if *l >= r {
+                    *l -= r;
+                    borrow = 0;
+                } else {
+                    *l += BASE - r;
+                    borrow = 1;
+                }
+            }
+            Some(Self::from_limbs(limbs))
+        }
+
+        /// Returns `self` to the power of `n`.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(\log n)$ multiplications
+        pub fn pow(&self, mut n: u32) -> Self {
+            let mut x = self.clone();
+            let mut r = Self::from(1u32);
+            while n > 0 {
+                if n & 1 == 1 {
+                    r *= &x;
+                }
+                n >>= 1;
+                if n > 0 {
+                    x = &x * &x;
+                }
+            }
+            r
+        }
+
+        /// Returns the quotient and the remainder of `self` divided by a small integer.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `rhs` is zero.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn div_rem_u32(&self, rhs: u32) -> (Self, u32) {
+            assert!(rhs != 0, "attempt to divide by zero");
+            let rhs = u64::from(rhs);
+            let mut q = vec![0; self.limbs.len()];
+            let mut r = 0;
+            for (q, &l) in q.iter_mut().zip(&self.limbs).rev() {
+                let x = r * u64::from(BASE) + u64::from(l);
+                *q = (x / rhs) as u32;
+                r = x % rhs;
+            }
+            (Self::from_limbs(q), r as u32)
+        }
+
+        /// Returns the quotient and the remainder of `self` divided by `rhs`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `rhs` is zero.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(nm)$ where $n$ and $m$ are the numbers of digits of `self` and `rhs`
+        #[allow(clippy::many_single_char_names)]
+        pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+            assert!(!rhs.is_zero(), "attempt to divide by zero");
+            if self < rhs {
+                return (Self::default(), self.clone());
+            }
+            if rhs.limbs.len() == 1 {
+                let (q, r) = self.div_rem_u32(rhs.limbs[0]);
+                return (q, r.into());
+            }
+
+            // Knuth's Algorithm D with the divisor normalized so that its top limb is at least `BASE / 2`
+            let base = u64::from(BASE);
+            let d = BASE / (rhs.limbs[rhs.limbs.len() - 1] + 1);
+            let mut u = (self * d).limbs;
+            u.resize(self.limbs.len() + 1, 0);
+            let v = (rhs * d).limbs;
+            let n = v.len();
+            let m = u.len() - n;
+            let (v1, v2) = (u64::from(v[n - 1]), u64::from(v[n - 2]));
+            let mut q = vec![0; m];
+            for j in (0..m).rev() {
+                let x = u64::from(u[j + n]) * base + u64::from(u[j + n - 1]);
+                let (mut qhat, mut rhat) = (x / v1, x % v1);
+                while qhat >= base || qhat * v2 > rhat * base + u64::from(u[j + n - 2]) {
+                    qhat -= 1;
+                    rhat += v1;
+                    if rhat >= base {
+                        break;
+                    }
+                }
+
+                let (mut carry, mut borrow) = (0, 0);
+                for (u, &v) in u[j..j + n].iter_mut().zip(&v) {
+                    let p = qhat * u64::from(v) + carry;
+                    carry = p / base;
+                    let t = i64::from(*u) - (p % base) as i64 - borrow;
+                    borrow = (t < 0) as i64;
+                    *u = (t + borrow * i64::from(BASE)) as u32;
+                }
+                let mut top = i64::from(u[j + n]) - carry as i64 - borrow;
+                if top < 0 {
+                    // `qhat` was one too large
+                    qhat -= 1;
+                    let mut carry = 0;
+                    for (u, &v) in u[j..j + n].iter_mut().zip(&v) {
+                        let s = *u + v + carry;
+                        carry = (s >= BASE) as u32;
+                        *u = s - carry * BASE;
+                    }
+                    top += i64::from(carry);
+                }
+                u[j + n] = top as u32;
+                q[j] = qhat as u32;
+            }
+            u.truncate(n);
+            let (r, _) = Self::from_limbs(u).div_rem_u32(d);
+            (Self::from_limbs(q), r)
+        }
+
+        fn from_limbs(mut limbs: Vec<u32>) -> Self {
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+            Self { limbs }
+        }
+
+        fn to_decimal(&self) -> String {
+            let mut s = String::with_capacity(self.limbs.len() * BASE_DIGITS);
+            match self.limbs.split_last() {
+                None => s.push('0'),
+                Some((last, rest)) => {
+                    write!(s, "{}", last).unwrap();
+                    for l in rest.iter().rev() {
+                        write!(s, "{:08}", l).unwrap();
+                    }
+                }
+            }
+            s
+        }
+    }
+
+    /// An arbitrary-precision signed integer.
+    ///
+    /// Division truncates toward zero and the remainder has the sign of the dividend, as the primitive integers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::BigInt;
+    ///
+    /// let a = "-100000000000000000000".parse::<BigInt>().unwrap();
+    /// let b = BigInt::from(7);
+    /// assert_eq!("-14285714285714285714", (&a / &b).to_string());
+    /// assert_eq!(BigInt::from(-2), &a % &b);
+    /// assert!(a < b);
+    /// ```
+    #[derive(Clone, PartialEq, Eq, Hash, Default)]
+    pub struct BigInt {
+        /// `false` if the value is zero.
+        neg: bool,
+        mag: BigUint,
+    }
+
+    impl BigInt {
+        /// Returns `true` if `self` is zero.
+        #[inline]
+        pub fn is_zero(&self) -> bool {
+            self.mag.is_zero()
+        }
+
+        /// Returns `true` if `self` is negative.
+        #[inline]
+        pub fn is_negative(&self) -> bool {
+            self.neg
+        }
+
+        /// Returns the absolute value of `self`.
+        #[inline]
+        pub fn magnitude(&self) -> &BigUint {
+            &self.mag
+        }
+
+        /// Returns the absolute value of `self`.
+        #[inline]
+        pub fn into_magnitude(self) -> BigUint {
+            self.mag
+        }
+
+        /// Returns `self` to the power of `n`.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(\log n)$ multiplications
+        pub fn pow(&self, n: u32) -> Self {
+            Self::with_sign(self.neg && n & 1 == 1, self.mag.pow(n))
+        }
+
+        /// Returns the quotient truncated toward zero and the remainder of `self` divided by `rhs`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `rhs` is zero.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(nm)$ where $n$ and $m$ are the numbers of digits of `self` and `rhs`
+        pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+            let (q, r) = self.mag.div_rem(&rhs.mag);
+            (
+                Self::with_sign(self.neg != rhs.neg, q),
+                Self::with_sign(self.neg, r),
+            )
+        }
+
+        fn with_sign(neg: bool, mag: BigUint) -> Self {
+            Self {
+                neg: neg && !mag.is_zero(),
+                mag,
+            }
+        }
+    }
+
+    /// An error which can be returned when parsing a [`BigUint`] or a [`BigInt`].
+    ///
+    /// [`BigUint`]: ./struct.BigUint.html
+    /// [`BigInt`]: ./struct.BigInt.html
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ParseBigIntError {
+        kind: ParseBigIntErrorKind,
+    }
+
+    impl ParseBigIntError {
+        /// Returns the detailed cause of parsing an integer failing.
+        #[inline]
+        pub fn kind(&self) -> &ParseBigIntErrorKind {
+            &self.kind
+        }
+    }
+
+    impl fmt::Display for ParseBigIntError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self.kind {
+                ParseBigIntErrorKind::Empty => "cannot parse integer from empty string",
+                ParseBigIntErrorKind::InvalidDigit => "invalid digit found in string",
+            })
+        }
+    }
+
+    impl std::error::Error for ParseBigIntError {}
+
+    /// Enum to store the various types of errors that can cause parsing an integer to fail.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ParseBigIntErrorKind {
+        /// Value being parsed is empty.
+        Empty,
+        /// Contains an invalid digit, including a sign without any digits and `-` for [`BigUint`].
+        ///
+        /// [`BigUint`]: ./struct.BigUint.html
+        InvalidDigit,
+    }
+
+    /// Parses `s` as a decimal integer and returns its sign and its absolute value.
+    fn parse_decimal(s: &str) -> Result<(bool, BigUint), ParseBigIntError> {
+        if s.is_empty() {
+            return Err(ParseBigIntError {
+                kind: ParseBigIntErrorKind::Empty,
+            });
+        }
+        let (neg, digits) = match s.as_bytes()[0] {
+            b'+' => (false, &s[1..]),
+            b'-' => (true, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigIntError {
+                kind: ParseBigIntErrorKind::InvalidDigit,
+            });
+        }
+        let limbs = digits
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |acc, &c| acc * 10 + u32::from(c - b'0'))
+            })
+            .collect();
+        Ok((neg, BigUint::from_limbs(limbs)))
+    }
+
+    impl FromStr for BigUint {
+        type Err = ParseBigIntError;
+
+        fn from_str(s: &str) -> Result<Self, ParseBigIntError> {
+            match parse_decimal(s)? {
+                (true, _) => Err(ParseBigIntError {
+                    kind: ParseBigIntErrorKind::InvalidDigit,
+                }),
+                (false, mag) => Ok(mag),
+            }
+        }
+    }
+
+    impl FromStr for BigInt {
+        type Err = ParseBigIntError;
+
+        fn from_str(s: &str) -> Result<Self, ParseBigIntError> {
+            let (neg, mag) = parse_decimal(s)?;
+            Ok(Self::with_sign(neg, mag))
+        }
+    }
+
+    impl fmt::Display for BigUint {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.pad_integral(true, "", &self.to_decimal())
+        }
+    }
+
+    impl fmt::Display for BigInt {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.pad_integral(!self.neg, "", &self.mag.to_decimal())
+        }
+    }
+
+    impl fmt::Debug for BigUint {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(self, f)
+        }
+    }
+
+    impl fmt::Debug for BigInt {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(self, f)
+        }
+    }
+
+    macro_rules! impl_from_unsigned {
+        ($($ty:tt),*) => {
+            $(
+                impl From<$ty> for BigUint {
+                    fn from(x: $ty) -> Self {
+                        let mut x = x as u128;
+                        let mut limbs = vec![];
+                        while x > 0 {
+                            limbs.push((x % u128::from(BASE)) as u32);
+                            x /= u128::from(BASE);
+                        }
+                        Self { limbs }
+                    }
+                }
+
+                impl From<$ty> for BigInt {
+                    #[inline]
+                    fn from(x: $ty) -> Self {
+                        BigUint::from(x).into()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+    macro_rules! impl_from_signed {
+        ($($ty:tt),*) => {
+            $(
+                impl From<$ty> for BigInt {
+                    #[inline]
+                    fn from(x: $ty) -> Self {
+                        let x = x as i128;
+                        Self::with_sign(x < 0, BigUint::from(x.wrapping_abs() as u128))
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+    impl From<BigUint> for BigInt {
+        #[inline]
+        fn from(mag: BigUint) -> Self {
+            Self { neg: false, mag }
+        }
+    }
+
+    impl Ord for BigUint {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.limbs
+                .len()
+                .cmp(&other.limbs.len())
+                .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+        }
+    }
+
+    impl PartialOrd for BigUint {
+        #[inline]
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for BigInt {
+        fn cmp(&self, other: &Self) -> Ordering {
+            match (self.neg, other.neg) {
+                (false, false) => self.mag.cmp(&other.mag),
+                (true, true) => other.mag.cmp(&self.mag),
+                (false, true) => Ordering::Greater,
+                (true, false) => Ordering::Less,
+            }
+        }
+    }
+
+    impl PartialOrd for BigInt {
+        #[inline]
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Neg for BigInt {
+        type Output = BigInt;
+
+        #[inline]
+        fn neg(self) -> BigInt {
+            Self::with_sign(!self.neg, self.mag)
+        }
+    }
+
+    impl Neg for &'_ BigInt {
+        type Output = BigInt;
+
+        #[inline]
+        fn neg(self) -> BigInt {
+            -self.clone()
+        }
+    }
+
+    impl Mul<u32> for BigUint {
+        type Output = BigUint;
+
+        #[inline]
+        fn mul(mut self, rhs: u32) -> BigUint {
+            self *= rhs;
+            self
+        }
+    }
+
+    impl Mul<u32> for &'_ BigUint {
+        type Output = BigUint;
+
+        #[inline]
+        fn mul(self, rhs: u32) -> BigUint {
+            self.clone() * rhs
+        }
+    }
+
+    impl MulAssign<u32> for BigUint {
+        #[allow(clippy::suspicious_op_assign_impl)]
+        fn mul_assign(&mut self, rhs: u32) {
+            if rhs == 0 {
+                self.limbs.clear();
+                return;
+            }
+            let mut carry = 0;
+            for l in &mut self.limbs {
+                let x = u64::from(*l) * u64::from(rhs) + carry;
+                *l = (x % u64::from(BASE)) as u32;
+                carry = x / u64::from(BASE);
+            }
+            while carry > 0 {
+                self.limbs.push((carry % u64::from(BASE)) as u32);
+                carry /= u64::from(BASE);
+            }
+        }
+    }
+
+    fn add(lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        let (lhs, rhs) = if lhs.limbs.len() >= rhs.limbs.len() {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+        let mut limbs = Vec::with_capacity(lhs.limbs.len() + 1);
+        let mut carry = 0;
+        for (i, &l) in lhs.limbs.iter().enumerate() {
+            let s = l + rhs.limbs.get(i).copied().unwrap_or(0) + carry;
+            carry = (s >= BASE) as u32;
+            limbs.push(s - carry * BASE);
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+        BigUint { limbs }
+    }
+
+    fn sub(lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        lhs.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+
+    fn mul(lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        let (a, b) = (&lhs.limbs, &rhs.limbs);
+        if a.is_empty() || b.is_empty() {
+            return BigUint::default();
+        }
+        if cmp::min(a.len(), b.len()) <= 60 {
+            return BigUint::from_limbs(mul_naive(a, b));
+        }
+
+        // Each coefficient in base 10^4 is less than 10^8 min(|a|, |b|) * 2, which fits in `i64`.
+        const HALF: u32 = 10_000;
+        let split = |a: &[u32]| {
+            a.iter()
+                .flat_map(|&a| iter::once(a % HALF).chain(iter::once(a / HALF)))
+                .map(i64::from)
+                .collect::<Vec<_>>()
+        };
+        let c = convolution::convolution_i64(&split(a), &split(b));
+        let mut digits = Vec::with_capacity(c.len() + 2);
+        let mut carry = 0;
+        for c in c {
+            let x = c as u64 + carry;
+            digits.push((x % u64::from(HALF)) as u32);
+            carry = x / u64::from(HALF);
+        }
+        while carry > 0 {
+            digits.push((carry % u64::from(HALF)) as u32);
+            carry /= u64::from(HALF);
+        }
+        let limbs = digits
+            .chunks(2)
+            .map(|d| d[0] + d.get(1).copied().unwrap_or(0) * HALF)
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    /// Multiplies the limbs in $O(|a| |b|)$, which requires $\min(|a|, |b|) \leq 1800$.
+    fn mul_naive(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut c = vec![0u64; a.len() + b.len()];
+        for (i, &a) in a.iter().enumerate() {
+            for (c, &b) in c[i..].iter_mut().zip(b) {
+                *c += u64::from(a) * u64::from(b);
+            }
+        }
+        let mut carry = 0;
+        c.into_iter()
+            .map(|c| {
+                let x = c + carry;
+                carry = x / u64::from(BASE);
+                (x % u64::from(BASE)) as u32
+            })
+            .collect()
+    }
+
+    fn div(lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        lhs.div_rem(rhs).0
+    }
+
+    fn rem(lhs: &BigUint, rhs: &BigUint) -> BigUint {
+        lhs.div_rem(rhs).1
+    }
+
+    fn add_signed(lhs: &BigInt, rhs_neg: bool, rhs: &BigUint) -> BigInt {
+        if lhs.neg == rhs_neg {
+            return BigInt::with_sign(lhs.neg, add(&lhs.mag, rhs));
+        }
+        match lhs.mag.cmp(rhs) {
+            Ordering::Less => BigInt::with_sign(rhs_neg, sub(rhs, &lhs.mag)),
+            _ => BigInt::with_sign(lhs.neg, sub(&lhs.mag, rhs)),
+        }
+    }
+
+    fn add_int(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+        add_signed(lhs, rhs.neg, &rhs.mag)
+    }
+
+    fn sub_int(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+        add_signed(lhs, !rhs.neg && !rhs.is_zero(), &rhs.mag)
+    }
+
+    fn mul_int(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+        BigInt::with_sign(lhs.neg != rhs.neg, mul(&lhs.mag, &rhs.mag))
+    }
+
+    fn div_int(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+        lhs.div_rem(rhs).0
+    }
+
+    fn rem_int(lhs: &BigInt, rhs: &BigInt) -> BigInt {
+        lhs.div_rem(rhs).1
+    }
+
+    macro_rules! impl_bin_ops {
+        ($(for $ty:ident: $op:ident::$method:ident, $op_assign:ident::$method_assign:ident => $f:ident;)*) => {
+            $(
+                impl $op<&'_ $ty> for &'_ $ty {
+                    type Output = $ty;
+
+                    #[inline]
+                    fn $method(self, rhs: &$ty) -> $ty {
+                        $f(self, rhs)
+                    }
+                }
+
+                impl $op<$ty> for &'_ $ty {
+                    type Output = $ty;
+
+                    #[inline]
+                    fn $method(self, rhs: $ty) -> $ty {
+                        $f(self, &rhs)
+                    }
+                }
+
+                impl $op<&'_ $ty> for $ty {
+                    type Output = $ty;
+
+                    #[inline]
+                    fn $method(self, rhs: &$ty) -> $ty {
+                        $f(&self, rhs)
+                    }
+                }
+
+                impl $op<$ty> for $ty {
+                    type Output = $ty;
+
+                    #[inline]
+                    fn $method(self, rhs: $ty) -> $ty {
+                        $f(&self, &rhs)
+                    }
+                }
+
+                impl $op_assign<&'_ $ty> for $ty {
+                    #[inline]
+                    fn $method_assign(&mut self, rhs: &$ty) {
+                        *self = $f(self, rhs);
+                    }
+                }
+
+                impl $op_assign<$ty> for $ty {
+                    #[inline]
+                    fn $method_assign(&mut self, rhs: $ty) {
+                        *self = $f(self, &rhs);
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_bin_ops! {
+        for BigUint: Add::add, AddAssign::add_assign => add;
+        for BigUint: Sub::sub, SubAssign::sub_assign => sub;
+        for BigUint: Mul::mul, MulAssign::mul_assign => mul;
+        for BigUint: Div::div, DivAssign::div_assign => div;
+        for BigUint: Rem::rem, RemAssign::rem_assign => rem;
+        for BigInt:  Add::add, AddAssign::add_assign => add_int;
+        for BigInt:  Sub::sub, SubAssign::sub_assign => sub_int;
+        for BigInt:  Mul::mul, MulAssign::mul_assign => mul_int;
+        for BigInt:  Div::div, DivAssign::div_assign => div_int;
+        for BigInt:  Rem::rem, RemAssign::rem_assign => rem_int;
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{BigInt, BigUint, ParseBigIntErrorKind};
+        use rand::{rngs::ThreadRng, Rng as _};
+
+        #[test]
+        fn parse_and_format() {
+            for &(s, expected) in &[
+                ("0", "0"),
+                ("+0", "0"),
+                ("000", "0"),
+                ("00012345678901234567", "12345678901234567"),
+                ("100000000", "100000000"),
+            ] {
+                assert_eq!(expected, s.parse::<BigUint>().unwrap().to_string());
+                assert_eq!(expected, s.parse::<BigInt>().unwrap().to_string());
+            }
+            assert_eq!("0", "-0".parse::<BigInt>().unwrap().to_string());
+            assert_eq!(
+                "-12345678901234567",
+                "-00012345678901234567"
+                    .parse::<BigInt>()
+                    .unwrap()
+                    .to_string(),
+            );
+            assert_eq!("  -42", format!("{:5}", BigInt::from(-42)));
+            assert_eq!("+0042", format!("{:+05}", BigUint::from(42u32)));
+
+            let mut rng = rand::thread_rng();
+            for n in 1..100 {
+                let s = gen_decimal(&mut rng, n);
+                assert_eq!(s, s.parse::<BigUint>().unwrap().to_string());
+            }
+
+            for &(s, kind) in &[
+                ("", ParseBigIntErrorKind::Empty),
+                ("+", ParseBigIntErrorKind::InvalidDigit),
+                ("-", ParseBigIntErrorKind::InvalidDigit),
+                ("1a", ParseBigIntErrorKind::InvalidDigit),
+                (" 1", ParseBigIntErrorKind::InvalidDigit),
+                ("--1", ParseBigIntErrorKind::InvalidDigit),
+            ] {
+                assert_eq!(&kind, s.parse::<BigUint>().unwrap_err().kind());
+                assert_eq!(&kind, s.parse::<BigInt>().unwrap_err().kind());
+            }
+            assert_eq!(
+                &ParseBigIntErrorKind::InvalidDigit,
+                "-1".parse::<BigUint>().unwrap_err().kind(),
+            );
+        }
+
+        #[test]
+        fn against_u128() {
+            let mut rng = rand::thread_rng();
+            for _ in 0..1000 {
+                let a = rng.gen::<u64>() >> rng.gen_range(0, 64);
+                let b = rng.gen::<u64>() >> rng.gen_range(0, 64);
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                let (a, b) = (u128::from(a), u128::from(b));
+                assert_eq!(BigUint::from(a + b), &x + &y);
+                assert_eq!(BigUint::from(a * b), &x * &y);
+                assert_eq!(a.checked_sub(b).map(BigUint::from), x.checked_sub(&y));
+                assert_eq!(a.cmp(&b), x.cmp(&y));
+                if b > 0 {
+                    assert_eq!(BigUint::from(a / b), &x / &y);
+                    assert_eq!(BigUint::from(a % b), &x % &y);
+                }
+                if b > 0 && b <= u128::from(u32::max_value()) {
+                    assert_eq!(
+                        (BigUint::from(a / b), (a % b) as u32),
+                        x.div_rem_u32(b as u32),
+                    );
+                    assert_eq!(BigUint::from(a * b), &x * b as u32);
+                }
+            }
+        }
+
+        #[test]
+        fn against_i128() {
+            let mut rng = rand::thread_rng();
+            for _ in 0..1000 {
+                let a = rng.gen::<i64>() >> rng.gen_range(0, 64);
+                let b = rng.gen::<i64>() >> rng.gen_range(0, 64);
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                let (a, b) = (i128::from(a), i128::from(b));
+                assert_eq!(BigInt::from(a + b), &x + &y);
+                assert_eq!(BigInt::from(a - b), &x - &y);
+                assert_eq!(BigInt::from(a * b), &x * &y);
+                assert_eq!(BigInt::from(-a), -&x);
+                assert_eq!(a.cmp(&b), x.cmp(&y));
+                assert_eq!(a.to_string(), x.to_string());
+                if b != 0 {
+                    assert_eq!(BigInt::from(a / b), &x / &y);
+                    assert_eq!(BigInt::from(a % b), &x % &y);
+                }
+            }
+            assert_eq!(
+                "-170141183460469231731687303715884105728",
+                BigInt::from(i128::min_value()).to_string()
+            );
+            assert_eq!(BigInt::from(-8), BigInt::from(-2).pow(3));
+            assert_eq!(BigInt::from(16), BigInt::from(-2).pow(4));
+        }
+
+        #[test]
+        fn mul_large() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(61, 61), (100, 1000), (1000, 1000), (1500, 700)] {
+                let a = gen(&mut rng, n * 8);
+                let b = gen(&mut rng, m * 8);
+                assert_eq!(super::mul_naive(&a.limbs, &b.limbs), (&a * &b).limbs);
+            }
+            let a = BigUint::from(99_999_999u32).pow(3000);
+            let mut expected = vec![1];
+            for _ in 0..3000 {
+                expected = super::mul_naive(&expected, &[99_999_999]);
+            }
+            assert_eq!(BigUint::from_limbs(expected), a);
+            assert_eq!(24_000, a.to_string().len());
+
+            let b = gen(&mut rng, 5000);
+            assert_eq!((a.clone(), BigUint::from(0u32)), (&a * &b).div_rem(&b));
+        }
+
+        #[test]
+        fn div_rem_large() {
+            let mut rng = rand::thread_rng();
+            for _ in 0..200 {
+                let n = rng.gen_range(1, 300);
+                let m = rng.gen_range(1, 300);
+                let a = gen(&mut rng, n);
+                let b = gen(&mut rng, m);
+                check_div_rem(&a, &b);
+                check_div_rem(&(&a * &b), &b);
+                check_div_rem(&(&a * &b + &b - BigUint::from(1u32)), &b);
+            }
+
+            // divisors which need the corrections of the estimated quotient
+            let one = BigUint::from(1u32);
+            for k in 2..20 {
+                let b = BigUint::from(10u32).pow(8 * k) - &one;
+                check_div_rem(&(&b * &b), &b);
+                check_div_rem(&(&b * &b - &one), &b);
+                let b = BigUint::from(10u32).pow(8 * k - 1) + &one;
+                check_div_rem(&(&b * &b * 7u32 - &one), &b);
+                let b = BigUint::from(5u32) * BigUint::from(10u32).pow(8 * k - 1);
+                check_div_rem(&(&b * &b - &one), &b);
+            }
+            assert_eq!(
+                (BigUint::default(), BigUint::from(5u32)),
+                BigUint::from(5u32).div_rem(&BigUint::from(6u32)),
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn div_by_zero() {
+            let _ = BigUint::from(1u32) / BigUint::default();
+        }
+
+        #[test]
+        #[should_panic]
+        fn sub_with_overflow() {
+            let _ = BigUint::from(1u32) - BigUint::from(2u32);
+        }
+
+        fn check_div_rem(a: &BigUint, b: &BigUint) {
+            if b.is_zero() {
+                return;
+            }
+            let (q, r) = a.div_rem(b);
+            assert!(r < *b);
+            assert_eq!(*a, &q * b + &r);
+        }
+
+        fn gen(rng: &mut ThreadRng, n: usize) -> BigUint {
+            gen_decimal(rng, n).parse().unwrap()
+        }
+
+        fn gen_decimal(rng: &mut ThreadRng, n: usize) -> String {
+            (0..n)
+                .map(|i| {
+                    let d = if i == 0 {
+                        rng.gen_range(1, 10)
+                    } else {
+                        rng.gen_range(0, 10)
+                    };
+                    char::from(b'0' + d)
+                })
+                .collect()
+        }
+    }
+}
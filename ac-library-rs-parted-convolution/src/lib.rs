@@ -1,4 +1,4 @@
-// This code was expanded by `xtask`.
+// This code was expanded by `xtask` and has been extended by hand since. `xtask` no longer regenerates it.
 
 extern crate __acl_internal_bit as internal_bit;
 extern crate __acl_internal_math as internal_math;
@@ -30,7 +30,9 @@ mod convolution {
 
     use super::{
         internal_bit, internal_math,
-        modint::{self, ButterflyCache, Modulus, RemEuclidU32, StaticModInt},
+        modint::{
+            self, ButterflyCache, ModIntBase, Modulus, MontgomeryModInt, RemEuclidU32, StaticModInt,
+        },
     };
     use std::{
         cmp,
@@ -59,22 +61,25 @@ mod convolution {
             return ans;
         }
 
-        let (mut a, mut b) = (a.to_owned(), b.to_owned());
         let z = 1 << internal_bit::ceil_pow2((n + m - 1) as _);
-        a.resize(z, StaticModInt::raw(0));
+        let mut a = to_montgomery(a, z);
         butterfly(&mut a);
-        b.resize(z, StaticModInt::raw(0));
+        let mut b = to_montgomery(b, z);
         butterfly(&mut b);
         for (a, b) in a.iter_mut().zip(&b) {
             *a *= b;
         }
         butterfly_inv(&mut a);
-        a.resize(n + m - 1, StaticModInt::raw(0));
-        let iz = StaticModInt::new(z).inv();
-        for a in &mut a {
-            *a *= iz;
-        }
-        a
+        let iz = MontgomeryModInt::new(z).inv();
+        a[..n + m - 1].iter().map(|&a| (a * iz).into()).collect()
+    }
+
+    /// Converts `a` into the Montgomery form, padding it with zeros to length `z`.
+    fn to_montgomery<M: Modulus>(a: &[StaticModInt<M>], z: usize) -> Vec<MontgomeryModInt<M>> {
+        let mut ret = Vec::with_capacity(z);
+        ret.extend(a.iter().map(|&a| MontgomeryModInt::from(a)));
+        ret.resize(z, MontgomeryModInt::raw(0));
+        ret
     }
 
     pub fn convolution_raw<T, M>(a: &[T], b: &[T]) -> Vec<T>
@@ -95,18 +100,20 @@ mod convolution {
             .collect()
     }
 
+    // The NTT-friendly primes for `convolution_i64` and `convolution_any_mod`.
+    const M1: u64 = 754_974_721; // 2^24
+    const M2: u64 = 167_772_161; // 2^25
+    const M3: u64 = 469_762_049; // 2^26
+
+    modulus!(M1, M2, M3);
+
     #[allow(clippy::many_single_char_names)]
     pub fn convolution_i64(a: &[i64], b: &[i64]) -> Vec<i64> {
-        const M1: u64 = 754_974_721; // 2^24
-        const M2: u64 = 167_772_161; // 2^25
-        const M3: u64 = 469_762_049; // 2^26
         const M2M3: u64 = M2 * M3;
         const M1M3: u64 = M1 * M3;
         const M1M2: u64 = M1 * M2;
         const M1M2M3: u64 = M1M2.wrapping_mul(M3);
 
-        modulus!(M1, M2, M3);
-
         if a.is_empty() || b.is_empty() {
             return vec![];
         }
@@ -159,8 +166,1031 @@ mod convolution {
             .collect()
     }
 
+    /// Calculates the convolution of `a` and `b` modulo the modulus of `Z`, which can be any modulus.
+    ///
+    /// The results for three NTT-friendly primes are combined by Garner's algorithm, so `Z` does not have to be
+    /// NTT-friendly and can be a [`DynamicModInt`] whose modulus is determined at runtime.
+    ///
+    /// # Constraints
+    ///
+    /// - $\min(|a|, |b|)(m - 1)^2 < 754974721 \cdot 167772161 \cdot 469762049 \approx 5.9 \times 10^{25}$, where $m$ is
+    ///   the modulus. This holds for $m \leq 10^9 + 7$ and $\min(|a|, |b|) \leq 5 \times 10^7$.
+    /// - $|a| + |b| - 1 \leq 2^{24}$
+    ///
+    /// # Complexity
+    ///
+    /// - $O((|a| + |b|) \log (|a| + |b|))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{convolution_any_mod, ModInt, ModInt1000000007};
+    ///
+    /// let a = [ModInt1000000007::new(1_000_000_006); 100];
+    /// let c = convolution_any_mod(&a, &a);
+    /// assert_eq!(ModInt1000000007::new(100), c[99]);
+    ///
+    /// ModInt::set_modulus(10);
+    /// let a = [ModInt::new(3); 100];
+    /// let c = convolution_any_mod(&a, &a);
+    /// assert_eq!(ModInt::new(9 * 100), c[99]);
+    /// ```
+    ///
+    /// [`DynamicModInt`]: ../acl_modint/struct.DynamicModInt.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn convolution_any_mod<Z: ModIntBase>(a: &[Z], b: &[Z]) -> Vec<Z> {
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let (n, m) = (a.len(), b.len());
+
+        if cmp::min(n, m) <= 60 {
+            let (n, m, a, b) = if n < m { (m, n, b, a) } else { (n, m, a, b) };
+            let mut ans = vec![Z::raw(0); n + m - 1];
+            for i in 0..n {
+                for j in 0..m {
+                    ans[i + j] += a[i] * b[j];
+                }
+            }
+            return ans;
+        }
+
+        let a = a.iter().map(|a| u64::from(a.val())).collect::<Vec<_>>();
+        let b = b.iter().map(|b| u64::from(b.val())).collect::<Vec<_>>();
+        let c1 = convolution_raw::<u64, M1>(&a, &b);
+        let c2 = convolution_raw::<u64, M2>(&a, &b);
+        let c3 = convolution_raw::<u64, M3>(&a, &b);
+
+        let modulus = u64::from(Z::modulus());
+        let (_, i1) = internal_math::inv_gcd(M1 as _, M2 as _);
+        let (_, i12) = internal_math::inv_gcd((M1 * M2 % M3) as _, M3 as _);
+        let (i1, i12) = (i1 as u64, i12 as u64);
+        let m1m2 = M1 * M2 % modulus;
+
+        c1.into_iter()
+            .zip(c2)
+            .zip(c3)
+            .map(|((c1, c2), c3)| {
+                // x = c1 + t1 M1 + t2 M1 M2
+                let t1 = (c2 + M2 - c1 % M2) % M2 * i1 % M2;
+                let x12 = c1 + t1 * M1;
+                let t2 = (c3 + M3 - x12 % M3) % M3 * i12 % M3;
+                Z::raw(((x12 % modulus + t2 * m1m2) % modulus) as _)
+            })
+            .collect()
+    }
+
+    /// Returns the first `len` coefficients of the convolution of `a` and `b`, padding them with zeros.
+    ///
+    /// Only the first `len` terms of `a` and `b` are used, so the transforms can be shorter than [`convolution`]'s.
+    ///
+    /// # Constraints
+    ///
+    /// - Same as [`convolution`].
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$ where $n = \min(|a| + |b|, 2 \cdot len)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{convolution_truncated, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// let b = [Mint::new(4), Mint::new(5), Mint::new(6)];
+    /// assert_eq!(vec![Mint::new(4), Mint::new(13)], convolution_truncated(&a, &b, 2));
+    /// ```
+    ///
+    /// [`convolution`]: ./fn.convolution.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn convolution_truncated<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+        len: usize,
+    ) -> Vec<StaticModInt<M>> {
+        let a = &a[..cmp::min(a.len(), len)];
+        let b = &b[..cmp::min(b.len(), len)];
+        let mut c = vec![StaticModInt::raw(0); len];
+        if a.is_empty() || b.is_empty() || cmp::min(a.len(), b.len()) <= 60 {
+            convolution_naive_into(a, b, &mut c);
+            return c;
+        }
+        let (n, m) = (a.len(), b.len());
+        let z = 1 << internal_bit::ceil_pow2((n + m - 1) as _);
+        let mut a = to_montgomery(a, z);
+        butterfly(&mut a);
+        let mut b = to_montgomery(b, z);
+        butterfly(&mut b);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a *= b;
+        }
+        butterfly_inv(&mut a);
+        store_montgomery(&a[..cmp::min(n + m - 1, len)], z, &mut c);
+        c
+    }
+
+    /// Calculates the cyclic convolution $c_k = \sum_{i + j \equiv k \pmod n} a_i b_j$ of length $n$.
+    ///
+    /// # Constraints
+    ///
+    /// - $|a| = |b| = n$
+    /// - $n$ is zero or a power of two that divides `M::VALUE - 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths are different or not a power of two.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{cyclic_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2)];
+    /// let b = [Mint::new(3), Mint::new(4)];
+    /// // (3 + 8, 4 + 6)
+    /// assert_eq!(vec![Mint::new(11), Mint::new(10)], cyclic_convolution(&a, &b));
+    /// ```
+    pub fn cyclic_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        assert_eq!(a.len(), b.len(), "the lengths are different");
+        let n = a.len();
+        if n == 0 {
+            return vec![];
+        }
+        assert!(n.is_power_of_two(), "the length is not a power of two");
+        let mut a = to_montgomery(a, n);
+        butterfly(&mut a);
+        let mut b = to_montgomery(b, n);
+        butterfly(&mut b);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a *= b;
+        }
+        butterfly_inv(&mut a);
+        let mut c = vec![StaticModInt::raw(0); n];
+        store_montgomery(&a, n, &mut c);
+        c
+    }
+
+    /// Calculates the middle product $c_k = \sum_{j = 0}^{m - 1} a_{k + j} b_{m - 1 - j}$ for $0 \leq k \leq n - m$,
+    /// where $n = |a|$ and $m = |b|$.
+    ///
+    /// This is the coefficients of degree $m - 1, \ldots, n - 1$ of the convolution of `a` and `b`, which are not
+    /// affected by the wrap-around of a cyclic convolution of length $n$. Reverse `b` to get sliding dot products.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq m \leq n$
+    /// - $n$ rounded up to a power of two divides `M::VALUE - 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` is empty or longer than `a`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{middle_product, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2), Mint::new(3), Mint::new(4)];
+    /// let b = [Mint::new(1), Mint::new(10)];
+    /// // (10 * 1 + 1 * 2, 10 * 2 + 1 * 3, 10 * 3 + 1 * 4)
+    /// assert_eq!(
+    ///     vec![Mint::new(12), Mint::new(23), Mint::new(34)],
+    ///     middle_product(&a, &b),
+    /// );
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn middle_product<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let (n, m) = (a.len(), b.len());
+        assert!(
+            1 <= m && m <= n,
+            "`b` must be non-empty and no longer than `a`"
+        );
+
+        let mut c = vec![StaticModInt::raw(0); n - m + 1];
+        if m <= 60 {
+            for (k, c) in c.iter_mut().enumerate() {
+                for (j, &b) in b.iter().rev().enumerate() {
+                    *c += a[k + j] * b;
+                }
+            }
+            return c;
+        }
+        let z = 1 << internal_bit::ceil_pow2(n as _);
+        let mut a = to_montgomery(a, z);
+        butterfly(&mut a);
+        let mut b = to_montgomery(b, z);
+        butterfly(&mut b);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a *= b;
+        }
+        butterfly_inv(&mut a);
+        store_montgomery(&a[m - 1..n], z, &mut c);
+        c
+    }
+
+    /// Calculates the two-dimensional convolution $c_{i, j} = \sum_{i_1 + i_2 = i, j_1 + j_2 = j} a_{i_1, j_1} b_{i_2, j_2}$.
+    ///
+    /// `a` and `b` are rectangular grids of $h_a \times w_a$ and $h_b \times w_b$, and the result is a grid of
+    /// $(h_a + h_b - 1) \times (w_a + w_b - 1)$. The result is empty if `a` or `b` has no elements.
+    ///
+    /// # Constraints
+    ///
+    /// - The rows of `a` have the same length, and so do the rows of `b`.
+    /// - $(h_a + h_b - 1)(w_a + w_b - 1) \leq 2^{23}$ (for `M` = 998244353)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not rectangular.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$ where $n = (h_a + h_b - 1)(w_a + w_b - 1)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{convolution_2d, ModInt998244353 as Mint};
+    ///
+    /// let a = vec![vec![Mint::new(1), Mint::new(2)], vec![Mint::new(3), Mint::new(4)]];
+    /// let b = vec![vec![Mint::new(1), Mint::new(1)]];
+    /// assert_eq!(
+    ///     vec![
+    ///         vec![Mint::new(1), Mint::new(3), Mint::new(2)],
+    ///         vec![Mint::new(3), Mint::new(7), Mint::new(4)],
+    ///     ],
+    ///     convolution_2d(&a, &b),
+    /// );
+    /// ```
+    pub fn convolution_2d<M: Modulus>(
+        a: &[Vec<StaticModInt<M>>],
+        b: &[Vec<StaticModInt<M>>],
+    ) -> Vec<Vec<StaticModInt<M>>> {
+        let flatten = |a: &[Vec<StaticModInt<M>>]| {
+            let w = a.first().map_or(0, Vec::len);
+            assert!(
+                a.iter().all(|a| a.len() == w),
+                "the grid is not rectangular"
+            );
+            (a.concat(), [a.len(), w])
+        };
+        let ((a, a_shape), (b, b_shape)) = (flatten(a), flatten(b));
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let (c, c_shape) = convolution_nd(&a, &a_shape, &b, &b_shape);
+        c.chunks(c_shape[1]).map(<[_]>::to_vec).collect()
+    }
+
+    /// Calculates the $d$-dimensional convolution of `a` and `b`, whose shapes are `a_shape` and `b_shape`, and
+    /// returns it with its shape.
+    ///
+    /// The arrays are flattened in the row-major order, and the result has the shape $s_i = (s_a)_i + (s_b)_i - 1$.
+    /// They are embedded into one-dimensional arrays with the strides of the result, so that [`convolution`]
+    /// calculates the result without any wrap-around.
+    ///
+    /// If `a` or `b` has no elements, this returns an empty array and the shape of zeros.
+    ///
+    /// # Constraints
+    ///
+    /// - $|s_a| = |s_b| = d$
+    /// - $|a| = \prod_i (s_a)_i$ and $|b| = \prod_i (s_b)_i$
+    /// - $\prod_i s_i \leq 2^{23}$ (for `M` = 998244353)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shapes have different dimensions or do not match the lengths.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n + d (|a| + |b|))$ where $n = \prod_i s_i$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{convolution_nd, ModInt998244353 as Mint};
+    ///
+    /// // (1 + x)(1 + y)(1 + z) * (1 + x)
+    /// let a = [Mint::new(1); 8];
+    /// let b = [Mint::new(1); 2];
+    /// let (c, shape) = convolution_nd(&a, &[2, 2, 2], &b, &[2, 1, 1]);
+    /// assert_eq!(vec![3, 2, 2], shape);
+    /// assert_eq!(Mint::new(2), c[1 * 4 + 1 * 2 + 1]); // [x y z]
+    /// assert_eq!(Mint::new(1), c[2 * 4 + 1 * 2 + 1]); // [x^2 y z]
+    /// ```
+    ///
+    /// [`convolution`]: ./fn.convolution.html
+    pub fn convolution_nd<M: Modulus>(
+        a: &[StaticModInt<M>],
+        a_shape: &[usize],
+        b: &[StaticModInt<M>],
+        b_shape: &[usize],
+    ) -> (Vec<StaticModInt<M>>, Vec<usize>) {
+        assert_eq!(
+            a_shape.len(),
+            b_shape.len(),
+            "the shapes have different dimensions"
+        );
+        assert_eq!(
+            a.len(),
+            a_shape.iter().product::<usize>(),
+            "the shape does not match the length"
+        );
+        assert_eq!(
+            b.len(),
+            b_shape.iter().product::<usize>(),
+            "the shape does not match the length"
+        );
+        if a.is_empty() || b.is_empty() {
+            return (vec![], vec![0; a_shape.len()]);
+        }
+
+        let c_shape = a_shape
+            .iter()
+            .zip(b_shape)
+            .map(|(&a, &b)| a + b - 1)
+            .collect::<Vec<_>>();
+        let mut strides = vec![1; c_shape.len()];
+        for i in (1..c_shape.len()).rev() {
+            strides[i - 1] = strides[i] * c_shape[i];
+        }
+        let embed = |a: &[StaticModInt<M>], shape: &[usize]| {
+            let len = shape
+                .iter()
+                .zip(&strides)
+                .map(|(&n, &stride)| (n - 1) * stride)
+                .sum::<usize>()
+                + 1;
+            let mut embedded = vec![StaticModInt::raw(0); len];
+            for (mut t, &a) in a.iter().enumerate() {
+                let mut pos = 0;
+                for (&n, &stride) in shape.iter().zip(&strides).rev() {
+                    pos += t % n * stride;
+                    t /= n;
+                }
+                embedded[pos] = a;
+            }
+            embedded
+        };
+        let c = convolution(&embed(a, a_shape), &embed(b, b_shape));
+        debug_assert_eq!(c_shape.iter().product::<usize>(), c.len());
+        (c, c_shape)
+    }
+
+    /// Calculates the XOR convolution $c_k = \sum_{i \oplus j = k} a_i b_j$ with the Walsh–Hadamard transform.
+    ///
+    /// `a` and `b` are padded with zeros to $n$, the length of the longer one rounded up to a power of two, and
+    /// the result has length $n$.
+    ///
+    /// # Constraints
+    ///
+    /// - $n <$ `M::VALUE`
+    /// - `M::VALUE` is odd.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{xor_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// let b = [Mint::new(4), Mint::new(5)];
+    /// // (1 * 4 + 2 * 5, 1 * 5 + 2 * 4, 3 * 4, 3 * 5)
+    /// assert_eq!(
+    ///     vec![Mint::new(14), Mint::new(13), Mint::new(12), Mint::new(15)],
+    ///     xor_convolution(&a, &b),
+    /// );
+    /// ```
+    pub fn xor_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let (mut a, mut b) = pad_to_power_of_two(a, b);
+        if a.is_empty() {
+            return a;
+        }
+        walsh_hadamard_transform(&mut a);
+        walsh_hadamard_transform(&mut b);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a *= b;
+        }
+        walsh_hadamard_transform(&mut a);
+        let iz = StaticModInt::new(a.len()).inv();
+        for a in &mut a {
+            *a *= iz;
+        }
+        a
+    }
+
+    /// Calculates the AND convolution $c_k = \sum_{i \land j = k} a_i b_j$ with the zeta and Möbius transforms over supersets.
+    ///
+    /// `a` and `b` are padded with zeros to $n$, the length of the longer one rounded up to a power of two, and
+    /// the result has length $n$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{and_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// let b = [Mint::new(4), Mint::new(5)];
+    /// // (1 * 4 + 1 * 5 + 2 * 4 + 3 * 4 + 3 * 5, 2 * 5, 0, 0)
+    /// assert_eq!(
+    ///     vec![Mint::new(44), Mint::new(10), Mint::new(0), Mint::new(0)],
+    ///     and_convolution(&a, &b),
+    /// );
+    /// ```
+    pub fn and_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let (mut a, mut b) = pad_to_power_of_two(a, b);
+        superset_transform(&mut a, false);
+        superset_transform(&mut b, false);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a *= b;
+        }
+        superset_transform(&mut a, true);
+        a
+    }
+
+    /// Calculates the OR convolution $c_k = \sum_{i \lor j = k} a_i b_j$ with the zeta and Möbius transforms over subsets.
+    ///
+    /// `a` and `b` are padded with zeros to $n$, the length of the longer one rounded up to a power of two, and
+    /// the result has length $n$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{or_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// let b = [Mint::new(4), Mint::new(5)];
+    /// // (1 * 4, 1 * 5 + 2 * 4 + 2 * 5, 3 * 4, 3 * 5)
+    /// assert_eq!(
+    ///     vec![Mint::new(4), Mint::new(23), Mint::new(12), Mint::new(15)],
+    ///     or_convolution(&a, &b),
+    /// );
+    /// ```
+    pub fn or_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let (mut a, mut b) = pad_to_power_of_two(a, b);
+        subset_transform(&mut a, false);
+        subset_transform(&mut b, false);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a *= b;
+        }
+        subset_transform(&mut a, true);
+        a
+    }
+
+    /// Calculates the subset convolution $c_k = \sum_{i \lor j = k, i \land j = 0} a_i b_j$ with the ranked zeta transform.
+    ///
+    /// `a` and `b` are padded with zeros to $n = 2^l$, the length of the longer one rounded up to a power of two,
+    /// and the result has length $n$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n l^2)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{subset_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// let b = [Mint::new(4), Mint::new(5)];
+    /// // (1 * 4, 1 * 5 + 2 * 4, 3 * 4, 3 * 5)
+    /// assert_eq!(
+    ///     vec![Mint::new(4), Mint::new(13), Mint::new(12), Mint::new(15)],
+    ///     subset_convolution(&a, &b),
+    /// );
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn subset_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let (a, b) = pad_to_power_of_two(a, b);
+        let n = a.len();
+        if n == 0 {
+            return a;
+        }
+        let l = n.trailing_zeros() as usize;
+
+        // ranked[r][s] = Σ_{t ⊆ s, |t| = r} a_t
+        let ranked = |a: &[StaticModInt<M>]| {
+            let mut ranked = vec![vec![StaticModInt::raw(0); n]; l + 1];
+            for (s, &a) in a.iter().enumerate() {
+                ranked[s.count_ones() as usize][s] = a;
+            }
+            for ranked in &mut ranked {
+                subset_transform(ranked, false);
+            }
+            ranked
+        };
+        let (ra, rb) = (ranked(&a), ranked(&b));
+
+        let mut c = vec![StaticModInt::raw(0); n];
+        let mut h = vec![StaticModInt::raw(0); n];
+        for r in 0..=l {
+            for h in &mut h {
+                *h = StaticModInt::raw(0);
+            }
+            for i in 0..=r {
+                for ((h, &a), &b) in h.iter_mut().zip(&ra[i]).zip(&rb[r - i]) {
+                    *h += a * b;
+                }
+            }
+            subset_transform(&mut h, true);
+            for (s, (c, &h)) in c.iter_mut().zip(&h).enumerate() {
+                if s.count_ones() as usize == r {
+                    *c = h;
+                }
+            }
+        }
+        c
+    }
+
+    /// Calculates the GCD convolution $c_k = \sum_{\gcd(i, j) = k} a_i b_j$ with the zeta and Möbius transforms over multiples.
+    ///
+    /// Index $0$ follows $\gcd(0, i) = i$. `a` and `b` are padded with zeros to the length of the longer one, which
+    /// is also the length of the result.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log \log n)$ where $n = \max(|a|, |b|)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{gcd_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(0), Mint::new(1), Mint::new(2), Mint::new(3), Mint::new(4)];
+    /// let b = [Mint::new(0), Mint::new(1), Mint::new(1), Mint::new(1), Mint::new(1)];
+    /// // c_2 = a_2 b_2 + a_2 b_4 + a_4 b_2
+    /// assert_eq!(Mint::new(8), gcd_convolution(&a, &b)[2]);
+    /// ```
+    pub fn gcd_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let n = cmp::max(a.len(), b.len());
+        let (a, b) = (pad(a, n), pad(b, n));
+        if n == 0 {
+            return a;
+        }
+        let primes = primes_below(n);
+        let mut c = a.clone();
+        let mut fb = b.clone();
+        multiple_transform(&mut c, &primes, false);
+        multiple_transform(&mut fb, &primes, false);
+        for (c, &b) in c.iter_mut().zip(&fb) {
+            *c *= b;
+        }
+        multiple_transform(&mut c, &primes, true);
+        c[0] = a[0] * b[0];
+        for k in 1..n {
+            c[k] += a[0] * b[k] + a[k] * b[0];
+        }
+        c
+    }
+
+    /// Calculates the LCM convolution $c_k = \sum_{\mathrm{lcm}(i, j) = k} a_i b_j$ with the zeta and Möbius
+    /// transforms over divisors.
+    ///
+    /// Index $0$ follows $\mathrm{lcm}(0, i) = 0$. `a` and `b` are padded with zeros to the length of the longer
+    /// one, which is also the length of the result; $c_k$ for larger $k$ are discarded.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log \log n)$ where $n = \max(|a|, |b|)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{lcm_convolution, ModInt998244353 as Mint};
+    ///
+    /// let a = [Mint::new(0), Mint::new(1), Mint::new(2), Mint::new(3), Mint::new(4)];
+    /// let b = [Mint::new(0), Mint::new(1), Mint::new(1), Mint::new(1), Mint::new(1)];
+    /// // c_4 = a_1 b_4 + a_2 b_4 + a_4 b_1 + a_4 b_2 + a_4 b_4
+    /// assert_eq!(Mint::new(15), lcm_convolution(&a, &b)[4]);
+    /// ```
+    pub fn lcm_convolution<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        let n = cmp::max(a.len(), b.len());
+        let (a, b) = (pad(a, n), pad(b, n));
+        if n == 0 {
+            return a;
+        }
+        let primes = primes_below(n);
+        let mut c = a.clone();
+        let mut fb = b.clone();
+        divisor_transform(&mut c, &primes, false);
+        divisor_transform(&mut fb, &primes, false);
+        for (c, &b) in c.iter_mut().zip(&fb) {
+            *c *= b;
+        }
+        divisor_transform(&mut c, &primes, true);
+        let sum_a = a.iter().sum::<StaticModInt<M>>();
+        let sum_b = b.iter().sum::<StaticModInt<M>>();
+        c[0] = a[0] * sum_b + sum_a * b[0] - a[0] * b[0];
+        c
+    }
+
+    fn pad<M: Modulus>(a: &[StaticModInt<M>], n: usize) -> Vec<StaticModInt<M>> {
+        let mut a = a.to_owned();
+        a.resize(n, StaticModInt::raw(0));
+        a
+    }
+
+    fn pad_to_power_of_two<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+    ) -> (Vec<StaticModInt<M>>, Vec<StaticModInt<M>>) {
+        let n = match cmp::max(a.len(), b.len()) {
+            0 => 0,
+            n => n.next_power_of_two(),
+        };
+        (pad(a, n), pad(b, n))
+    }
+
+    #[allow(clippy::many_single_char_names)]
+    fn walsh_hadamard_transform<M: Modulus>(a: &mut [StaticModInt<M>]) {
+        let n = a.len();
+        let mut w = 1;
+        while w < n {
+            for i in (0..n).filter(|&i| i & w == 0) {
+                let (x, y) = (a[i], a[i | w]);
+                a[i] = x + y;
+                a[i | w] = x - y;
+            }
+            w <<= 1;
+        }
+    }
+
+    /// Replaces $a_s$ with $\sum_{t \subseteq s} a_t$, or inverts it if `inverse`.
+    fn subset_transform<M: Modulus>(a: &mut [StaticModInt<M>], inverse: bool) {
+        let n = a.len();
+        let mut w = 1;
+        while w < n {
+            for i in (0..n).filter(|&i| i & w == 0) {
+                let l = a[i];
+                if inverse {
+                    a[i | w] -= l;
+                } else {
+                    a[i | w] += l;
+                }
+            }
+            w <<= 1;
+        }
+    }
+
+    /// Replaces $a_s$ with $\sum_{t \supseteq s} a_t$, or inverts it if `inverse`.
+    fn superset_transform<M: Modulus>(a: &mut [StaticModInt<M>], inverse: bool) {
+        let n = a.len();
+        let mut w = 1;
+        while w < n {
+            for i in (0..n).filter(|&i| i & w == 0) {
+                let r = a[i | w];
+                if inverse {
+                    a[i] -= r;
+                } else {
+                    a[i] += r;
+                }
+            }
+            w <<= 1;
+        }
+    }
+
+    /// Replaces $a_d$ with $\sum_{d | k} a_k$ for $d \geq 1$, or inverts it if `inverse`.
+    fn multiple_transform<M: Modulus>(a: &mut [StaticModInt<M>], primes: &[usize], inverse: bool) {
+        let n = a.len();
+        for &p in primes {
+            if inverse {
+                for i in 1..=(n - 1) / p {
+                    let r = a[i * p];
+                    a[i] -= r;
+                }
+            } else {
+                for i in (1..=(n - 1) / p).rev() {
+                    let r = a[i * p];
+                    a[i] += r;
+                }
+            }
+        }
+    }
+
+    /// Replaces $a_k$ with $\sum_{d | k} a_d$ for $k \geq 1$, or inverts it if `inverse`.
+    fn divisor_transform<M: Modulus>(a: &mut [StaticModInt<M>], primes: &[usize], inverse: bool) {
+        let n = a.len();
+        for &p in primes {
+            if inverse {
+                for i in (1..=(n - 1) / p).rev() {
+                    let l = a[i];
+                    a[i * p] -= l;
+                }
+            } else {
+                for i in 1..=(n - 1) / p {
+                    let l = a[i];
+                    a[i * p] += l;
+                }
+            }
+        }
+    }
+
+    fn primes_below(n: usize) -> Vec<usize> {
+        let mut sieve = vec![true; n];
+        let mut primes = vec![];
+        for p in 2..n {
+            if sieve[p] {
+                primes.push(p);
+                for j in (p * p..n).step_by(p) {
+                    sieve[j] = false;
+                }
+            }
+        }
+        primes
+    }
+
+    /// Calculates convolutions reusing its scratch buffers.
+    ///
+    /// [`convolution`] allocates the buffers for the transforms on each call. `Convolver` keeps them between calls
+    /// and can write the result into a caller-provided slice, so once the buffers have grown to the largest length,
+    /// repeated calls do not allocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{convolution::Convolver, ModInt998244353 as Mint};
+    ///
+    /// let mut convolver = Convolver::new();
+    /// let mut c = [Mint::new(0); 3];
+    /// convolver.convolution_into(&[Mint::new(1), Mint::new(2)], &[Mint::new(3), Mint::new(4)], &mut c);
+    /// assert_eq!([Mint::new(3), Mint::new(10), Mint::new(8)], c);
+    ///
+    /// convolver.square_into(&[Mint::new(1), Mint::new(1)], &mut c);
+    /// assert_eq!([Mint::new(1), Mint::new(2), Mint::new(1)], c);
+    /// ```
+    ///
+    /// [`convolution`]: ./fn.convolution.html
+    #[derive(Clone)]
+    pub struct Convolver<M: Modulus> {
+        a: Vec<MontgomeryModInt<M>>,
+        b: Vec<MontgomeryModInt<M>>,
+    }
+
+    impl<M: Modulus> Convolver<M> {
+        /// Creates a new `Convolver` with empty buffers.
+        #[inline]
+        pub fn new() -> Self {
+            Self {
+                a: vec![],
+                b: vec![],
+            }
+        }
+
+        /// Calculates the convolution of `a` and `b`.
+        ///
+        /// The result is the same as [`convolution`].
+        ///
+        /// [`convolution`]: ./fn.convolution.html
+        pub fn convolution(
+            &mut self,
+            a: &[StaticModInt<M>],
+            b: &[StaticModInt<M>],
+        ) -> Vec<StaticModInt<M>> {
+            if a.is_empty() || b.is_empty() {
+                return vec![];
+            }
+            let mut c = vec![StaticModInt::raw(0); a.len() + b.len() - 1];
+            self.convolution_into(a, b, &mut c);
+            c
+        }
+
+        /// Writes the convolution of `a` and `b` into `c`.
+        ///
+        /// `c[k]` is set to the coefficient of degree `k`, so the product is truncated if `c` is shorter than
+        /// `a.len() + b.len() - 1` and padded with zeros if longer.
+        ///
+        /// # Constraints
+        ///
+        /// - Same as [`convolution`].
+        ///
+        /// [`convolution`]: ./fn.convolution.html
+        #[allow(clippy::many_single_char_names)]
+        pub fn convolution_into(
+            &mut self,
+            a: &[StaticModInt<M>],
+            b: &[StaticModInt<M>],
+            c: &mut [StaticModInt<M>],
+        ) {
+            if a.is_empty() || b.is_empty() || cmp::min(a.len(), b.len()) <= 60 {
+                convolution_naive_into(a, b, c);
+                return;
+            }
+            let (n, m) = (a.len(), b.len());
+            let z = 1 << internal_bit::ceil_pow2((n + m - 1) as _);
+            load_montgomery(&mut self.a, a, z);
+            load_montgomery(&mut self.b, b, z);
+            butterfly(&mut self.a);
+            butterfly(&mut self.b);
+            for (a, &b) in self.a.iter_mut().zip(&self.b) {
+                *a *= b;
+            }
+            butterfly_inv(&mut self.a);
+            store_montgomery(&self.a[..n + m - 1], z, c);
+        }
+
+        /// Calculates the convolution of `a` and itself.
+        pub fn square(&mut self, a: &[StaticModInt<M>]) -> Vec<StaticModInt<M>> {
+            if a.is_empty() {
+                return vec![];
+            }
+            let mut c = vec![StaticModInt::raw(0); 2 * a.len() - 1];
+            self.square_into(a, &mut c);
+            c
+        }
+
+        /// Writes the convolution of `a` and itself into `c`, with one forward transform.
+        ///
+        /// `c` is treated in the same way as [`convolution_into`].
+        ///
+        /// [`convolution_into`]: ./struct.Convolver.html#method.convolution_into
+        pub fn square_into(&mut self, a: &[StaticModInt<M>], c: &mut [StaticModInt<M>]) {
+            if a.len() <= 60 {
+                convolution_naive_into(a, a, c);
+                return;
+            }
+            let n = a.len();
+            let z = 1 << internal_bit::ceil_pow2((2 * n - 1) as _);
+            load_montgomery(&mut self.a, a, z);
+            butterfly(&mut self.a);
+            for a in &mut self.a {
+                *a *= *a;
+            }
+            butterfly_inv(&mut self.a);
+            store_montgomery(&self.a[..2 * n - 1], z, c);
+        }
+    }
+
+    impl<M: Modulus> Default for Convolver<M> {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    fn convolution_naive_into<M: Modulus>(
+        a: &[StaticModInt<M>],
+        b: &[StaticModInt<M>],
+        c: &mut [StaticModInt<M>],
+    ) {
+        for c in &mut *c {
+            *c = StaticModInt::raw(0);
+        }
+        for (i, &a) in a.iter().enumerate().take(c.len()) {
+            for (j, &b) in b.iter().enumerate().take(c.len() - i) {
+                c[i + j] += a * b;
+            }
+        }
+    }
+
+    /// Overwrites `buf` with `a` in the Montgomery form, padding it with zeros to length `z`.
+    fn load_montgomery<M: Modulus>(
+        buf: &mut Vec<MontgomeryModInt<M>>,
+        a: &[StaticModInt<M>],
+        z: usize,
+    ) {
+        buf.clear();
+        buf.extend(a.iter().map(|&a| MontgomeryModInt::from(a)));
+        buf.resize(z, MontgomeryModInt::raw(0));
+    }
+
+    /// Writes `a / z` into `c`, padding it with zeros.
+    fn store_montgomery<M: Modulus>(
+        a: &[MontgomeryModInt<M>],
+        z: usize,
+        c: &mut [StaticModInt<M>],
+    ) {
+        let iz = MontgomeryModInt::new(z).inv();
+        for (i, c) in c.iter_mut().enumerate() {
+            *c = a.get(i).map_or(StaticModInt::raw(0), |&a| (a * iz).into());
+        }
+    }
+
+    /// Performs the number theoretic transform in place, leaving the result in bit-reversed order.
+    ///
+    /// # Constraints
+    ///
+    /// - `a.len()` is a power of two that divides `M::VALUE - 1`.
+    #[inline]
+    pub fn butterfly<M: Modulus>(a: &mut [MontgomeryModInt<M>]) {
+        butterfly_impl::<M, _>(a);
+    }
+
+    /// Inverts [`butterfly`] in place, except that the result is multiplied by `a.len()`.
+    ///
+    /// # Constraints
+    ///
+    /// - `a.len()` is a power of two that divides `M::VALUE - 1`.
+    ///
+    /// [`butterfly`]: ./fn.butterfly.html
+    #[inline]
+    pub fn butterfly_inv<M: Modulus>(a: &mut [MontgomeryModInt<M>]) {
+        butterfly_inv_impl::<M, _>(a);
+    }
+
+    /// Performs the number theoretic transform in place.
+    ///
+    /// The result is in bit-reversed order, which does not matter for pointwise products. Use [`intt`] to transform
+    /// them back. [`butterfly`] is faster since it works in the Montgomery form.
+    ///
+    /// # Constraints
+    ///
+    /// - `a.len()` is a power of two that divides `M::VALUE - 1`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{convolution, ModInt998244353 as Mint};
+    ///
+    /// let mut a = vec![Mint::new(1), Mint::new(2), Mint::new(0), Mint::new(0)];
+    /// let mut b = vec![Mint::new(3), Mint::new(4), Mint::new(0), Mint::new(0)];
+    /// convolution::ntt(&mut a);
+    /// convolution::ntt(&mut b);
+    /// for (a, b) in a.iter_mut().zip(&b) {
+    ///     *a *= b;
+    /// }
+    /// convolution::intt(&mut a);
+    /// assert_eq!(vec![Mint::new(3), Mint::new(10), Mint::new(8), Mint::new(0)], a);
+    /// ```
+    ///
+    /// [`intt`]: ./fn.intt.html
+    /// [`butterfly`]: ./fn.butterfly.html
+    #[inline]
+    pub fn ntt<M: Modulus>(a: &mut [StaticModInt<M>]) {
+        butterfly_impl::<M, _>(a);
+    }
+
+    /// Inverts [`ntt`] in place.
+    ///
+    /// # Constraints
+    ///
+    /// - `a.len()` is a power of two that divides `M::VALUE - 1`.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// [`ntt`]: ./fn.ntt.html
+    pub fn intt<M: Modulus>(a: &mut [StaticModInt<M>]) {
+        butterfly_inv_impl::<M, _>(a);
+        let iz = StaticModInt::new(a.len()).inv();
+        for a in a {
+            *a *= iz;
+        }
+    }
+
     #[allow(clippy::many_single_char_names)]
-    fn butterfly<M: Modulus>(a: &mut [StaticModInt<M>]) {
+    fn butterfly_impl<M, Z>(a: &mut [Z])
+    where
+        M: Modulus,
+        Z: ModIntBase + From<StaticModInt<M>>,
+    {
         let n = a.len();
         let h = internal_bit::ceil_pow2(n as u32);
 
@@ -170,7 +1200,7 @@ mod convolution {
             for ph in 1..=h {
                 let w = 1 << (ph - 1);
                 let p = 1 << (h - ph);
-                let mut now = StaticModInt::<M>::new(1);
+                let mut now = Z::raw(1);
                 for s in 0..w {
                     let offset = s << (h - ph + 1);
                     for i in 0..p {
@@ -179,14 +1209,18 @@ mod convolution {
                         a[i + offset] = l + r;
                         a[i + offset + p] = l - r;
                     }
-                    now *= sum_e[(!s).trailing_zeros() as usize];
+                    now *= Z::from(sum_e[(!s).trailing_zeros() as usize]);
                 }
             }
         });
     }
 
     #[allow(clippy::many_single_char_names)]
-    fn butterfly_inv<M: Modulus>(a: &mut [StaticModInt<M>]) {
+    fn butterfly_inv_impl<M, Z>(a: &mut [Z])
+    where
+        M: Modulus,
+        Z: ModIntBase + From<StaticModInt<M>>,
+    {
         let n = a.len();
         let h = internal_bit::ceil_pow2(n as u32);
 
@@ -196,16 +1230,16 @@ mod convolution {
             for ph in (1..=h).rev() {
                 let w = 1 << (ph - 1);
                 let p = 1 << (h - ph);
-                let mut inow = StaticModInt::<M>::new(1);
+                let mut inow = Z::raw(1);
                 for s in 0..w {
                     let offset = s << (h - ph + 1);
                     for i in 0..p {
                         let l = a[i + offset];
                         let r = a[i + offset + p];
                         a[i + offset] = l + r;
-                        a[i + offset + p] = StaticModInt::new(M::VALUE + l.val() - r.val()) * inow;
+                        a[i + offset + p] = (l - r) * inow;
                     }
-                    inow *= sum_ie[(!s).trailing_zeros() as usize];
+                    inow *= Z::from(sum_ie[(!s).trailing_zeros() as usize]);
                 }
             }
         });
@@ -246,10 +1280,38 @@ mod convolution {
         use super::super::modint::{self, Mod998244353, Modulus, RemEuclidU32, StaticModInt};
         use rand::{rngs::ThreadRng, Rng as _};
         use std::{
+            alloc::{GlobalAlloc, Layout, System},
+            cell::Cell,
+            cmp,
             convert::{TryFrom, TryInto as _},
             fmt,
         };
 
+        /// Counts the allocations made by each thread.
+        struct CountingAllocator;
+
+        thread_local! {
+            static ALLOCATIONS: Cell<usize> = Cell::new(0);
+        }
+
+        unsafe impl GlobalAlloc for CountingAllocator {
+            unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+                System.alloc(layout)
+            }
+
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                System.dealloc(ptr, layout)
+            }
+        }
+
+        #[global_allocator]
+        static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+        fn allocations() -> usize {
+            ALLOCATIONS.with(Cell::get)
+        }
+
         //https://github.com/atcoder/ac-library/blob/8250de484ae0ab597391db58040a602e0dc1a419/test/unittest/convolution_test.cpp#L51-L71
         #[test]
         fn empty() {
@@ -431,6 +1493,383 @@ mod convolution {
             assert_eq!(conv_naive(&a, &b), super::convolution(&a, &b));
         }
 
+        #[test]
+        fn conv_any_mod() {
+            use super::super::modint::{
+                define_id, DynamicModInt, ModInt1000000007, ModInt998244353, ModIntBase,
+            };
+
+            fn naive<Z: ModIntBase>(a: &[Z], b: &[Z]) -> Vec<Z> {
+                let mut c = vec![Z::raw(0); a.len() + b.len() - 1];
+                for (i, &a) in a.iter().enumerate() {
+                    for (j, &b) in b.iter().enumerate() {
+                        c[i + j] += a * b;
+                    }
+                }
+                c
+            }
+
+            fn gen<Z: ModIntBase>(rng: &mut ThreadRng, n: usize) -> Vec<Z> {
+                (0..n)
+                    .map(|_| Z::raw(rng.gen_range(0, Z::modulus())))
+                    .collect()
+            }
+
+            define_id!(thread_local Id;);
+            type Z = DynamicModInt<Id>;
+
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[
+                (1, 1),
+                (1, 100),
+                (60, 60),
+                (61, 61),
+                (100, 1000),
+                (1234, 2345),
+            ] {
+                let (a, b) = (gen::<ModInt1000000007>(&mut rng, n), gen(&mut rng, m));
+                assert_eq!(naive(&a, &b), super::convolution_any_mod(&a, &b));
+
+                let (a, b) = (gen::<ModInt998244353>(&mut rng, n), gen(&mut rng, m));
+                assert_eq!(
+                    super::convolution(&a, &b),
+                    super::convolution_any_mod(&a, &b)
+                );
+
+                for &modulus in &[1, 2, 1_000_000_009, 2_147_483_647] {
+                    Z::set_modulus(modulus);
+                    let (a, b) = (gen::<Z>(&mut rng, n), gen(&mut rng, m));
+                    assert_eq!(naive(&a, &b), super::convolution_any_mod(&a, &b));
+                }
+            }
+
+            // the largest values
+            Z::set_modulus(1_000_000_007);
+            let a = vec![Z::new(-1); 5000];
+            let c = super::convolution_any_mod(&a, &a);
+            for (i, c) in c.into_iter().enumerate() {
+                assert_eq!(Z::new(cmp::min(i + 1, 9999 - i)), c);
+            }
+        }
+
+        #[test]
+        fn ntt() {
+            let mut rng = rand::thread_rng();
+            for &n in &[1, 2, 4, 64, 1024] {
+                let a = gen_values::<Mod998244353>(&mut rng, n);
+                let mut b = a.clone();
+                super::ntt(&mut b);
+                super::intt(&mut b);
+                assert_eq!(a, b);
+
+                let (mut a, mut b) = (gen_values(&mut rng, n / 2), gen_values(&mut rng, n / 2));
+                let c = if n == 1 {
+                    vec![]
+                } else {
+                    conv_naive::<Mod998244353>(&a, &b)
+                };
+                a.resize(n, 0.into());
+                b.resize(n, 0.into());
+                super::ntt(&mut a);
+                super::ntt(&mut b);
+                for (a, b) in a.iter_mut().zip(&b) {
+                    *a *= b;
+                }
+                super::intt(&mut a);
+                assert_eq!(c[..], a[..c.len()]);
+            }
+        }
+
+        #[test]
+        fn convolver() {
+            let mut rng = rand::thread_rng();
+            let mut convolver = super::Convolver::<Mod998244353>::new();
+            for &(n, m) in &[
+                (1, 1),
+                (60, 1000),
+                (61, 61),
+                (1000, 100),
+                (100, 1000),
+                (2, 3),
+            ] {
+                let (a, b) = (gen_values(&mut rng, n), gen_values(&mut rng, m));
+                let c = conv_naive(&a, &b);
+                assert_eq!(c, convolver.convolution(&a, &b));
+                assert_eq!(conv_naive(&a, &a), convolver.square(&a));
+
+                for &len in &[0, 1, n + m - 2, n + m - 1, n + m + 10] {
+                    let mut out = vec![StaticModInt::new(1); len];
+                    convolver.convolution_into(&a, &b, &mut out);
+                    for (k, &out) in out.iter().enumerate() {
+                        assert_eq!(c.get(k).copied().unwrap_or_default(), out);
+                    }
+                }
+            }
+            assert!(convolver.convolution(&[], &[1.into()]).is_empty());
+            assert!(convolver.square(&[]).is_empty());
+            let mut out = [StaticModInt::new(1); 2];
+            convolver.square_into(&[], &mut out);
+            assert_eq!([StaticModInt::new(0); 2], out);
+        }
+
+        #[test]
+        fn convolver_does_not_allocate_after_warming_up() {
+            let mut rng = rand::thread_rng();
+            let inputs = (0..10)
+                .map(|_| (gen_values(&mut rng, 500), gen_values(&mut rng, 300)))
+                .collect::<Vec<_>>();
+            let expected = inputs
+                .iter()
+                .map(|(a, b)| (conv_naive::<Mod998244353>(a, b), conv_naive(a, a)))
+                .collect::<Vec<_>>();
+
+            let mut convolver = super::Convolver::new();
+            let mut c = vec![StaticModInt::raw(0); 799];
+            let mut d = vec![StaticModInt::raw(0); 999];
+            convolver.convolution_into(&inputs[0].0, &inputs[0].1, &mut c);
+            convolver.square_into(&inputs[0].0, &mut d);
+
+            let before = allocations();
+            for ((a, b), (ab, aa)) in inputs.iter().zip(&expected) {
+                convolver.convolution_into(a, b, &mut c);
+                assert_eq!(ab[..], c[..]);
+                convolver.square_into(a, &mut d);
+                assert_eq!(aa[..], d[..]);
+            }
+            assert_eq!(before, allocations());
+        }
+
+        #[test]
+        fn truncated() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(1, 1), (70, 100), (100, 70), (1000, 2000), (3, 500)] {
+                let (a, b) = (gen_values(&mut rng, n), gen_values(&mut rng, m));
+                let c = conv_naive::<Mod998244353>(&a, &b);
+                for &len in &[0, 1, 50, n + m - 2, n + m - 1, n + m + 10] {
+                    let expected = (0..len)
+                        .map(|k| c.get(k).copied().unwrap_or_default())
+                        .collect::<Vec<_>>();
+                    assert_eq!(expected, super::convolution_truncated(&a, &b, len));
+                }
+            }
+            assert_eq!(
+                vec![StaticModInt::<Mod998244353>::raw(0); 2],
+                super::convolution_truncated(&[], &[1.into()], 2),
+            );
+        }
+
+        #[test]
+        fn cyclic() {
+            let mut rng = rand::thread_rng();
+            for &n in &[1, 2, 4, 64, 1024] {
+                let (a, b) = (gen_values(&mut rng, n), gen_values(&mut rng, n));
+                let mut expected = vec![StaticModInt::<Mod998244353>::raw(0); n];
+                for (k, c) in conv_naive(&a, &b).into_iter().enumerate() {
+                    expected[k % n] += c;
+                }
+                assert_eq!(expected, super::cyclic_convolution(&a, &b));
+            }
+            assert!(super::cyclic_convolution::<Mod998244353>(&[], &[]).is_empty());
+        }
+
+        #[test]
+        #[should_panic]
+        fn cyclic_with_non_power_of_two() {
+            let a = [StaticModInt::<Mod998244353>::raw(1); 3];
+            super::cyclic_convolution(&a, &a);
+        }
+
+        #[test]
+        fn middle() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[
+                (1, 1),
+                (100, 1),
+                (100, 60),
+                (100, 61),
+                (1000, 1000),
+                (1025, 700),
+            ] {
+                let (a, b) = (gen_values(&mut rng, n), gen_values(&mut rng, m));
+                let c = conv_naive::<Mod998244353>(&a, &b);
+                assert_eq!(c[m - 1..n], super::middle_product(&a, &b)[..]);
+            }
+        }
+
+        #[test]
+        fn nd() {
+            let mut rng = rand::thread_rng();
+            for (a_shape, b_shape) in vec![
+                (vec![], vec![]),
+                (vec![5], vec![70]),
+                (vec![3, 4], vec![5, 1]),
+                (vec![30, 20], vec![10, 40]),
+                (vec![2, 3, 4], vec![4, 3, 2]),
+                (vec![1, 7, 1, 3], vec![2, 1, 5, 3]),
+            ] {
+                let a = gen_values::<Mod998244353>(&mut rng, a_shape.iter().product());
+                let b = gen_values(&mut rng, b_shape.iter().product());
+                let c_shape = a_shape
+                    .iter()
+                    .zip(&b_shape)
+                    .map(|(&a, &b)| a + b - 1)
+                    .collect::<Vec<usize>>();
+                let index = |shape: &[usize], mut t: usize| {
+                    let mut idx = vec![0; shape.len()];
+                    for (i, &n) in shape.iter().enumerate().rev() {
+                        idx[i] = t % n;
+                        t /= n;
+                    }
+                    idx
+                };
+                let mut expected = vec![StaticModInt::raw(0); c_shape.iter().product()];
+                for (i, &a) in a.iter().enumerate() {
+                    for (j, &b) in b.iter().enumerate() {
+                        let k = index(&a_shape, i)
+                            .iter()
+                            .zip(index(&b_shape, j))
+                            .zip(&c_shape)
+                            .fold(0, |k, ((&i, j), &n)| k * n + i + j);
+                        expected[k] += a * b;
+                    }
+                }
+                assert_eq!(
+                    (expected, c_shape),
+                    super::convolution_nd(&a, &a_shape, &b, &b_shape),
+                );
+            }
+            assert_eq!(
+                (vec![], vec![0, 0]),
+                super::convolution_nd::<Mod998244353>(&[], &[0, 3], &[1.into()], &[1, 1]),
+            );
+        }
+
+        #[test]
+        fn two_d() {
+            let mut rng = rand::thread_rng();
+            for &(ha, wa, hb, wb) in &[(1, 1, 1, 1), (3, 70, 2, 80), (40, 30, 20, 10)] {
+                let a = (0..ha)
+                    .map(|_| gen_values::<Mod998244353>(&mut rng, wa))
+                    .collect::<Vec<_>>();
+                let b = (0..hb)
+                    .map(|_| gen_values(&mut rng, wb))
+                    .collect::<Vec<_>>();
+                let mut expected = vec![vec![StaticModInt::raw(0); wa + wb - 1]; ha + hb - 1];
+                for (i, j) in (0..ha).flat_map(|i| (0..wa).map(move |j| (i, j))) {
+                    for (k, l) in (0..hb).flat_map(|k| (0..wb).map(move |l| (k, l))) {
+                        expected[i + k][j + l] += a[i][j] * b[k][l];
+                    }
+                }
+                assert_eq!(expected, super::convolution_2d(&a, &b));
+            }
+            assert!(super::convolution_2d::<Mod998244353>(&[], &[vec![1.into()]]).is_empty());
+            assert!(super::convolution_2d::<Mod998244353>(&[vec![]], &[vec![1.into()]]).is_empty());
+        }
+
+        #[test]
+        #[should_panic]
+        fn two_d_with_non_rectangular() {
+            let a = vec![vec![StaticModInt::<Mod998244353>::raw(1); 2], vec![]];
+            super::convolution_2d(&a, &a);
+        }
+
+        #[test]
+        fn bitwise() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[
+                (0, 0),
+                (0, 3),
+                (1, 1),
+                (5, 3),
+                (8, 8),
+                (100, 37),
+                (1000, 1024),
+            ] {
+                let (a, b) = (
+                    gen_values::<Mod998244353>(&mut rng, n),
+                    gen_values(&mut rng, m),
+                );
+                let z = if n == 0 && m == 0 {
+                    0
+                } else {
+                    cmp::max(n, m).next_power_of_two()
+                };
+                let mut xor = vec![StaticModInt::raw(0); z];
+                let mut and = vec![StaticModInt::raw(0); z];
+                let mut or = vec![StaticModInt::raw(0); z];
+                let mut subset = vec![StaticModInt::raw(0); z];
+                for (i, j) in (0..n).flat_map(|i| (0..m).map(move |j| (i, j))) {
+                    xor[i ^ j] += a[i] * b[j];
+                    and[i & j] += a[i] * b[j];
+                    or[i | j] += a[i] * b[j];
+                    if i & j == 0 {
+                        subset[i | j] += a[i] * b[j];
+                    }
+                }
+                assert_eq!(xor, super::xor_convolution(&a, &b));
+                assert_eq!(and, super::and_convolution(&a, &b));
+                assert_eq!(or, super::or_convolution(&a, &b));
+                if z <= 256 {
+                    assert_eq!(subset, super::subset_convolution(&a, &b));
+                }
+            }
+        }
+
+        #[test]
+        fn subset() {
+            let mut rng = rand::thread_rng();
+            let (a, b) = (
+                gen_values::<Mod998244353>(&mut rng, 1 << 12),
+                gen_values(&mut rng, 1 << 12),
+            );
+            let c = super::subset_convolution(&a, &b);
+            for _ in 0..100 {
+                let k = rng.gen_range(0, 1 << 12);
+                let mut expected = StaticModInt::raw(0);
+                let mut i = k;
+                loop {
+                    expected += a[i] * b[k ^ i];
+                    if i == 0 {
+                        break;
+                    }
+                    i = (i - 1) & k;
+                }
+                assert_eq!(expected, c[k]);
+            }
+        }
+
+        #[test]
+        fn gcd_lcm() {
+            fn gcd(a: usize, b: usize) -> usize {
+                if b == 0 {
+                    a
+                } else {
+                    gcd(b, a % b)
+                }
+            }
+
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(0, 0), (0, 3), (1, 1), (2, 5), (30, 30), (300, 100)] {
+                let (a, b) = (
+                    gen_values::<Mod998244353>(&mut rng, n),
+                    gen_values(&mut rng, m),
+                );
+                let z = cmp::max(n, m);
+                let mut gcd_conv = vec![StaticModInt::raw(0); z];
+                let mut lcm_conv = vec![StaticModInt::raw(0); z];
+                for (i, j) in (0..n).flat_map(|i| (0..m).map(move |j| (i, j))) {
+                    let g = gcd(i, j);
+                    gcd_conv[g] += a[i] * b[j];
+                    let l = if g == 0 { 0 } else { i / g * j };
+                    if l < z {
+                        lcm_conv[l] += a[i] * b[j];
+                    }
+                }
+                assert_eq!(gcd_conv, super::gcd_convolution(&a, &b));
+                assert_eq!(lcm_conv, super::lcm_convolution(&a, &b));
+            }
+        }
+
         #[allow(clippy::many_single_char_names)]
         fn conv_naive<M: Modulus>(
             a: &[StaticModInt<M>],
@@ -0,0 +1,445 @@
+//! Convolutions with the floating-point fast Fourier transform.
+//!
+//! [`convolution_f64`] convolves real sequences such as probability distributions, and [`convolution_u128`]
+//! calculates the exact convolution of `u64` sequences whose result does not fit in 64 bits by splitting the values
+//! into small digits. Both run the complex FFT on `f64` with twiddle factors computed directly by `sin` and `cos`,
+//! and pack two real sequences into one complex sequence to halve the number of transforms.
+//!
+//! # Example
+//!
+//! ```
+//! use ac_library_rs::fft::{convolution_f64, convolution_u128};
+//!
+//! let c = convolution_f64(&[0.5, 0.5], &[0.25, 0.75]);
+//! assert_eq!(vec![0.125, 0.5, 0.375], c);
+//!
+//! let c = convolution_u128(&[u64::max_value(), 2], &[u64::max_value(), 3]);
+//! let x = u128::from(u64::max_value());
+//! assert_eq!(vec![x * x, 5 * x, 6], c);
+//! ```
+//!
+//! [`convolution_f64`]: ./fn.convolution_f64.html
+//! [`convolution_u128`]: ./fn.convolution_u128.html
+
+#[cfg(test)]
+extern crate __acl_convolution as convolution;
+#[cfg(test)]
+extern crate __acl_modint as modint;
+
+pub use self::fft::*;
+
+mod fft {
+    use std::{
+        cmp,
+        f64::consts::PI,
+        ops::{Add, Mul, Sub},
+    };
+
+    /// Calculates the convolution $c_k = \sum_{i + j = k} a_i b_j$ of real sequences.
+    ///
+    /// `a` and `b` are scaled by powers of two so that their maximum absolute values are about $1$, and packed into
+    /// the real and imaginary parts of one complex sequence, whose square has $2 (a * b)$ as its imaginary part.
+    ///
+    /// # Errors
+    ///
+    /// Each $c_k$ has an absolute error of $O(\epsilon \log N \|a\|_2 \|b\|_2)$, where $\epsilon = 2^{-53}$ and $N$
+    /// is $|a| + |b| - 1$ rounded up to a power of two. For two sequences of length $10^6$, the error is about
+    /// $10^{-12}$ with values in $[-1, 1]$ and $10^{-10}$ with values in $[0, 1]$. The relative error is not bounded,
+    /// so coefficients much smaller than the others may lose all of their significant digits.
+    ///
+    /// If $\min(|a|, |b|) \leq 60$, the convolution is calculated naively without errors other than those of the
+    /// additions.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(N \log N)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::fft::convolution_f64;
+    ///
+    /// // The sum of two dice
+    /// let dice = [1.0 / 6.0; 6];
+    /// let sum = convolution_f64(&[&[0.0][..], &dice].concat(), &[&[0.0][..], &dice].concat());
+    /// assert!((sum[7] - 1.0 / 6.0).abs() < 1e-15);
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn convolution_f64(a: &[f64], b: &[f64]) -> Vec<f64> {
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let (n, m) = (a.len(), b.len());
+        if cmp::min(n, m) <= 60 {
+            let mut c = vec![0.0; n + m - 1];
+            for (i, &a) in a.iter().enumerate() {
+                for (j, &b) in b.iter().enumerate() {
+                    c[i + j] += a * b;
+                }
+            }
+            return c;
+        }
+        let (sa, sb) = (scale(a), scale(b));
+        let z = (n + m - 1).next_power_of_two();
+        let roots = roots(z);
+        let mut f = vec![Complex::default(); z];
+        for (f, &a) in f.iter_mut().zip(a) {
+            f.re = a / sa;
+        }
+        for (f, &b) in f.iter_mut().zip(b) {
+            f.im = b / sb;
+        }
+        fft(&mut f, &roots, false);
+        // (a + ib)^2 = a^2 - b^2 + 2iab
+        for f in &mut f {
+            *f = *f * *f;
+        }
+        fft(&mut f, &roots, true);
+        let k = sa * sb / (2 * z) as f64;
+        f[..n + m - 1].iter().map(|f| f.im * k).collect()
+    }
+
+    /// Calculates the convolution $c_k = \sum_{i + j = k} a_i b_j \bmod 2^{128}$ of unsigned integers exactly.
+    ///
+    /// The values are split into $w$-bit digits with $w \leq 16$ chosen so that each convolution of two digit
+    /// sequences is below $2^{46}$. The products of the digits are grouped by the sum of the positions in the
+    /// frequency domain, and two groups share one inverse transform.
+    ///
+    /// # Errors
+    ///
+    /// The error of each group before rounding is $O(2^{-7} \log_2 N)$ in the worst case, where $N$ is
+    /// $|a| + |b| - 1$ rounded up to a power of two, and the rounding recovers the exact values. Sequences of
+    /// `u64::MAX`, which maximize all the digits, give the exact result for $N$ up to $2^{22}$, and random values
+    /// are tested against [`convolution`] on the NTT.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(d N \log N + d^2 N)$ where $d = \lceil 64 / w \rceil$ is between $4$ and $6$ for practical lengths
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::fft::convolution_u128;
+    ///
+    /// let a = [1 << 63, 3];
+    /// let b = [1 << 62, 5];
+    /// assert_eq!(vec![1 << 125, 13 << 62, 15], convolution_u128(&a, &b));
+    /// ```
+    ///
+    /// [`convolution`]: ../acl_convolution/fn.convolution.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn convolution_u128(a: &[u64], b: &[u64]) -> Vec<u128> {
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let (n, m) = (a.len(), b.len());
+        if cmp::min(n, m) <= 60 {
+            let mut c = vec![0u128; n + m - 1];
+            for (i, &a) in a.iter().enumerate() {
+                for (j, &b) in b.iter().enumerate() {
+                    c[i + j] = c[i + j].wrapping_add(u128::from(a) * u128::from(b));
+                }
+            }
+            return c;
+        }
+        let z = (n + m - 1).next_power_of_two();
+        // z 2^(2w) <= 2^46
+        let w = cmp::max(
+            1,
+            cmp::min(16, (46usize.saturating_sub(z.trailing_zeros() as _)) / 2),
+        );
+        let d = (64 + w - 1) / w;
+        let roots = roots(z);
+
+        // the transforms of the p-th digits of `a` and `b`
+        let (mut fa, mut fb) = (Vec::with_capacity(d), Vec::with_capacity(d));
+        for p in 0..d {
+            let digit = |x: u64| ((x >> (w * p)) & ((1 << w) - 1)) as f64;
+            let mut f = vec![Complex::default(); z];
+            for (f, &a) in f.iter_mut().zip(a) {
+                f.re = digit(a);
+            }
+            for (f, &b) in f.iter_mut().zip(b) {
+                f.im = digit(b);
+            }
+            fft(&mut f, &roots, false);
+            // F_k = A_k + i B_k and conj(F_{-k}) = A_k - i B_k
+            let (mut x, mut y) = (vec![Complex::default(); z], vec![Complex::default(); z]);
+            for k in 0..z {
+                let (u, v) = (f[k], f[(z - k) & (z - 1)].conj());
+                x[k] = Complex::new((u.re + v.re) / 2.0, (u.im + v.im) / 2.0);
+                y[k] = Complex::new((u.im - v.im) / 2.0, (v.re - u.re) / 2.0);
+            }
+            fa.push(x);
+            fb.push(y);
+        }
+
+        let mut c = vec![0u128; n + m - 1];
+        for s in (0..2 * d - 1).step_by(2) {
+            // the groups of the positions `s` and `s + 1` in the real and imaginary parts
+            let mut g = vec![Complex::default(); z];
+            for (p, fa) in fa.iter().enumerate() {
+                for (q, fb) in fb.iter().enumerate() {
+                    if p + q == s {
+                        for ((g, &x), &y) in g.iter_mut().zip(fa).zip(fb) {
+                            *g = *g + x * y;
+                        }
+                    } else if p + q == s + 1 {
+                        for ((g, &x), &y) in g.iter_mut().zip(fa).zip(fb) {
+                            let t = x * y;
+                            *g = *g + Complex::new(-t.im, t.re);
+                        }
+                    }
+                }
+            }
+            fft(&mut g, &roots, true);
+            for (c, g) in c.iter_mut().zip(&g) {
+                let lo = (g.re / z as f64).round().max(0.0) as u128;
+                *c = c.wrapping_add(lo << (w * s));
+                if s + 1 < 2 * d - 1 {
+                    let hi = (g.im / z as f64).round().max(0.0) as u128;
+                    *c = c.wrapping_add(hi << (w * (s + 1)));
+                }
+            }
+        }
+        c
+    }
+
+    #[derive(Clone, Copy, Default)]
+    struct Complex {
+        re: f64,
+        im: f64,
+    }
+
+    impl Complex {
+        #[inline]
+        fn new(re: f64, im: f64) -> Self {
+            Self { re, im }
+        }
+
+        #[inline]
+        fn conj(self) -> Self {
+            Self::new(self.re, -self.im)
+        }
+    }
+
+    impl Add for Complex {
+        type Output = Self;
+
+        #[inline]
+        fn add(self, rhs: Self) -> Self {
+            Self::new(self.re + rhs.re, self.im + rhs.im)
+        }
+    }
+
+    impl Sub for Complex {
+        type Output = Self;
+
+        #[inline]
+        fn sub(self, rhs: Self) -> Self {
+            Self::new(self.re - rhs.re, self.im - rhs.im)
+        }
+    }
+
+    impl Mul for Complex {
+        type Output = Self;
+
+        #[inline]
+        fn mul(self, rhs: Self) -> Self {
+            Self::new(
+                self.re * rhs.re - self.im * rhs.im,
+                self.re * rhs.im + self.im * rhs.re,
+            )
+        }
+    }
+
+    /// Returns a power of two close to the maximum absolute value of `a`, or $1$ if there is no such one.
+    fn scale(a: &[f64]) -> f64 {
+        let max = a.iter().fold(0.0f64, |max, a| max.max(a.abs()));
+        if max.is_normal() {
+            2f64.powi(max.log2().round() as i32)
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns $e^{2 \pi i k / z}$ for $0 \leq k < z / 2$.
+    fn roots(z: usize) -> Vec<Complex> {
+        (0..z / 2)
+            .map(|k| {
+                let t = 2.0 * PI * k as f64 / z as f64;
+                Complex::new(t.cos(), t.sin())
+            })
+            .collect()
+    }
+
+    /// Transforms `a` in place with $e^{2 \pi i / z}$, or its conjugate if `inverse`. The inverse is not divided by $z$.
+    #[allow(clippy::many_single_char_names)]
+    fn fft(a: &mut [Complex], roots: &[Complex], inverse: bool) {
+        let z = a.len();
+        let mut j = 0;
+        for i in 1..z {
+            let mut bit = z >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                a.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= z {
+            let step = z / len;
+            for chunk in a.chunks_mut(len) {
+                let (l, r) = chunk.split_at_mut(len / 2);
+                for (k, (l, r)) in l.iter_mut().zip(r).enumerate() {
+                    let w = if inverse {
+                        roots[k * step].conj()
+                    } else {
+                        roots[k * step]
+                    };
+                    let (x, y) = (*l, *r * w);
+                    *l = x + y;
+                    *r = x - y;
+                }
+            }
+            len <<= 1;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::{
+            convolution,
+            modint::{self, ButterflyCache, Modulus, StaticModInt},
+        };
+        use rand::{rngs::ThreadRng, Rng as _};
+        use std::{cell::RefCell, thread::LocalKey};
+
+        #[test]
+        fn empty() {
+            assert!(super::convolution_f64(&[], &[]).is_empty());
+            assert!(super::convolution_f64(&[], &[1.0]).is_empty());
+            assert!(super::convolution_u128(&[1], &[]).is_empty());
+        }
+
+        #[test]
+        fn f64_against_ntt() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[
+                (1, 1),
+                (60, 1000),
+                (61, 61),
+                (1000, 2000),
+                (1 << 15, 1 << 15),
+            ] {
+                let a = (0..n)
+                    .map(|_| rng.gen_range(-1000, 1001))
+                    .collect::<Vec<i64>>();
+                let b = (0..m)
+                    .map(|_| rng.gen_range(-1000, 1001))
+                    .collect::<Vec<i64>>();
+                let expected = convolution::convolution_i64(&a, &b);
+                let c = super::convolution_f64(&to_f64(&a), &to_f64(&b));
+                assert_eq!(expected.len(), c.len());
+                for (&expected, &c) in expected.iter().zip(&c) {
+                    assert_eq!(expected, c.round() as i64);
+                }
+            }
+        }
+
+        #[test]
+        fn f64_error() {
+            let mut rng = rand::thread_rng();
+            let n = 100_000usize;
+            let a = (0..n)
+                .map(|_| rng.gen_range(-1.0, 1.0))
+                .collect::<Vec<f64>>();
+            let b = (0..n)
+                .map(|_| rng.gen_range(-1.0, 1.0) * 1e-12)
+                .collect::<Vec<f64>>();
+            let c = super::convolution_f64(&a, &b);
+            for _ in 0..100 {
+                let k = rng.gen_range(0, 2 * n - 1);
+                let expected = (k.saturating_sub(n - 1)..=k.min(n - 1))
+                    .map(|i| a[i] * b[k - i])
+                    .sum::<f64>();
+                assert!((expected - c[k]).abs() < 1e-20);
+            }
+        }
+
+        #[test]
+        fn u128_naive() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(1, 1), (60, 1000), (61, 61), (1000, 2000)] {
+                for &bits in &[1, 32, 64] {
+                    let (a, b) = (gen(&mut rng, n, bits), gen(&mut rng, m, bits));
+                    let mut expected = vec![0u128; n + m - 1];
+                    for (i, &a) in a.iter().enumerate() {
+                        for (j, &b) in b.iter().enumerate() {
+                            expected[i + j] =
+                                expected[i + j].wrapping_add(u128::from(a) * u128::from(b));
+                        }
+                    }
+                    assert_eq!(expected, super::convolution_u128(&a, &b));
+                }
+            }
+        }
+
+        #[test]
+        fn u128_against_ntt() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(5000, 5000), (1 << 17, 1 << 16)] {
+                // the results do not overflow
+                let (a, b) = (gen(&mut rng, n, 64), gen(&mut rng, m, 47));
+                let c = super::convolution_u128(&a, &b);
+                check_mod::<modint::Mod998244353>(&a, &b, &c);
+                check_mod::<Mod469762049>(&a, &b, &c);
+
+                let (a, b) = (vec![u64::max_value(); n], vec![u64::max_value(); m]);
+                let c = super::convolution_u128(&a, &b);
+                let x = u128::from(u64::max_value()).pow(2);
+                for (k, &c) in c.iter().enumerate() {
+                    let t = (k + 1).min(n).min(m).min(n + m - 1 - k) as u128;
+                    assert_eq!(x.wrapping_mul(t), c);
+                }
+            }
+        }
+
+        #[derive(Copy, Clone, Eq, PartialEq)]
+        enum Mod469762049 {}
+
+        impl Modulus for Mod469762049 {
+            const VALUE: u32 = 469_762_049;
+            const HINT_VALUE_IS_PRIME: bool = true;
+
+            fn butterfly_cache() -> &'static LocalKey<RefCell<Option<ButterflyCache<Self>>>> {
+                thread_local! {
+                    static BUTTERFLY_CACHE: RefCell<Option<ButterflyCache<Mod469762049>>> = Default::default();
+                }
+                &BUTTERFLY_CACHE
+            }
+        }
+
+        fn check_mod<M: Modulus>(a: &[u64], b: &[u64], c: &[u128]) {
+            let reduce = |a: &[u64]| {
+                a.iter()
+                    .map(|&a| StaticModInt::<M>::new(a))
+                    .collect::<Vec<_>>()
+            };
+            let expected = convolution::convolution(&reduce(a), &reduce(b));
+            let c = c.iter().map(|&c| StaticModInt::new(c)).collect::<Vec<_>>();
+            assert_eq!(expected, c);
+        }
+
+        fn to_f64(a: &[i64]) -> Vec<f64> {
+            a.iter().map(|&a| a as f64).collect()
+        }
+
+        fn gen(rng: &mut ThreadRng, n: usize, bits: u32) -> Vec<u64> {
+            (0..n).map(|_| rng.gen::<u64>() >> (64 - bits)).collect()
+        }
+    }
+}
@@ -0,0 +1,1140 @@
+//! Formal power series.
+//!
+//! [`Fps`] is a polynomial of [`StaticModInt<M>`] which supports the operations on formal power series such as
+//! inverse, logarithm, exponential, power and square root. They run in _O(n log n)_ with Newton's method on top of
+//! [`butterfly`] and [`butterfly_inv`], so `M` must be NTT-friendly, i.e. a prime such that `M::VALUE - 1` is
+//! divisible by a large power of two, like _998244353_.
+//!
+//! # Example
+//!
+//! ```
+//! use ac_library_rs::{Fps, ModInt998244353 as Mint};
+//!
+//! // 1 / (1 - x) = 1 + x + x^2 + ...
+//! let f = Fps::new(vec![Mint::new(1), -Mint::new(1)]);
+//! assert_eq!(&[Mint::new(1); 4], f.inv(4).coeffs());
+//!
+//! // exp(x) = 1 + x + x^2/2! + x^3/3! + ...
+//! let f = Fps::new(vec![Mint::new(0), Mint::new(1)]);
+//! assert_eq!(
+//!     &[Mint::new(1), Mint::new(1), Mint::new(2).inv(), Mint::new(6).inv()],
+//!     f.exp(4).coeffs(),
+//! );
+//! ```
+//!
+//! [`Fps`]: ./struct.Fps.html
+//! [`StaticModInt<M>`]: ../acl_modint/struct.StaticModInt.html
+//! [`butterfly`]: ../acl_convolution/fn.butterfly.html
+//! [`butterfly_inv`]: ../acl_convolution/fn.butterfly_inv.html
+
+extern crate __acl_convolution as convolution;
+extern crate __acl_modint as modint;
+
+pub use self::fps::*;
+
+mod fps {
+    use super::{
+        convolution::{self, butterfly, butterfly_inv},
+        modint::{self, Binomial, ModIntBase, Modulus, MontgomeryModInt, StaticModInt},
+    };
+    use std::{
+        fmt,
+        iter::{self, FromIterator},
+        ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    };
+
+    /// A formal power series $\sum_i a_i x^i$ over `StaticModInt<M>`.
+    ///
+    /// The coefficients are stored in ascending order of degree. Trailing zeros are allowed, and most of the
+    /// operations take the number of terms `n` to compute, returning the result modulo $x^n$.
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct Fps<M: Modulus> {
+        coeffs: Vec<StaticModInt<M>>,
+    }
+
+    impl<M: Modulus> Fps<M> {
+        /// Creates a new `Fps` from the coefficients in ascending order of degree.
+        #[inline]
+        pub fn new(coeffs: Vec<StaticModInt<M>>) -> Self {
+            Self { coeffs }
+        }
+
+        /// Returns the coefficients.
+        #[inline]
+        pub fn coeffs(&self) -> &[StaticModInt<M>] {
+            &self.coeffs
+        }
+
+        /// Returns the coefficients as a mutable slice.
+        #[inline]
+        pub fn coeffs_mut(&mut self) -> &mut [StaticModInt<M>] {
+            &mut self.coeffs
+        }
+
+        /// Converts `self` into the coefficients.
+        #[inline]
+        pub fn into_coeffs(self) -> Vec<StaticModInt<M>> {
+            self.coeffs
+        }
+
+        /// Returns the number of the stored coefficients.
+        #[inline]
+        pub fn len(&self) -> usize {
+            self.coeffs.len()
+        }
+
+        /// Returns `true` if no coefficients are stored.
+        #[inline]
+        pub fn is_empty(&self) -> bool {
+            self.coeffs.is_empty()
+        }
+
+        /// Returns the coefficient of $x^i$, which is zero if `i >= self.len()`.
+        #[inline]
+        pub fn coeff(&self, i: usize) -> StaticModInt<M> {
+            self.coeffs.get(i).copied().unwrap_or_default()
+        }
+
+        /// Shortens `self` to at most `n` terms.
+        #[inline]
+        pub fn truncate(&mut self, n: usize) {
+            self.coeffs.truncate(n);
+        }
+
+        /// Returns `self` modulo $x^n$ as exactly `n` terms, padding it with zeros if necessary.
+        pub fn prefix(&self, n: usize) -> Self {
+            (0..n).map(|i| self.coeff(i)).collect()
+        }
+
+        /// Returns the formal derivative of `self`.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn derivative(&self) -> Self {
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &a)| a * StaticModInt::new(i))
+                .collect()
+        }
+
+        /// Returns the formal integral of `self` whose constant term is zero.
+        ///
+        /// # Constraints
+        ///
+        /// - `self.len() < M::VALUE`
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn integral(&self) -> Self {
+            let inv = inverses::<M>(self.len());
+            iter::once(StaticModInt::raw(0))
+                .chain(self.coeffs.iter().zip(&inv[1..]).map(|(&a, &i)| a * i))
+                .collect()
+        }
+
+        /// Returns $g$ such that $fg \equiv 1 \pmod{x^n}$.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the constant term is zero.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n \log n)$
+        #[allow(clippy::many_single_char_names)]
+        pub fn inv(&self, n: usize) -> Self {
+            let a0 = self.coeff(0);
+            if a0.val() == 0 {
+                panic!("the constant term is zero");
+            }
+
+            // g ← g - (fg - 1)g, where fg - 1 ≡ 0 (mod x^m)
+            let mut g = vec![MontgomeryModInt::from(a0.inv())];
+            let mut m = 1;
+            while m < n {
+                let iz = MontgomeryModInt::new(2 * m).inv();
+                let mut f = (0..2 * m)
+                    .map(|i| MontgomeryModInt::from(self.coeff(i)))
+                    .collect::<Vec<_>>();
+                let mut h = g.clone();
+                h.resize(2 * m, MontgomeryModInt::raw(0));
+                butterfly(&mut f);
+                butterfly(&mut h);
+                for (f, &h) in f.iter_mut().zip(&h) {
+                    *f *= h;
+                }
+                butterfly_inv(&mut f);
+                // The lower half is 1 + (wrapped-around terms).
+                for f in &mut f[..m] {
+                    *f = MontgomeryModInt::raw(0);
+                }
+                for f in &mut f[m..] {
+                    *f *= iz;
+                }
+                butterfly(&mut f);
+                for (f, &h) in f.iter_mut().zip(&h) {
+                    *f *= h;
+                }
+                butterfly_inv(&mut f);
+                g.extend(f[m..].iter().map(|&f| -(f * iz)));
+                m *= 2;
+            }
+            g.truncate(n);
+            g.into_iter().map(Into::into).collect()
+        }
+
+        /// Returns $\log f \bmod x^n$.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the constant term is not one.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n \log n)$
+        pub fn log(&self, n: usize) -> Self {
+            if self.coeff(0).val() != 1 {
+                panic!("the constant term is not one");
+            }
+            if n == 0 {
+                return Self::default();
+            }
+            (self.prefix(n).derivative() * self.inv(n))
+                .prefix(n - 1)
+                .integral()
+        }
+
+        /// Returns $\exp f \bmod x^n$.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the constant term is not zero.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n \log n)$
+        pub fn exp(&self, n: usize) -> Self {
+            if self.coeff(0).val() != 0 {
+                panic!("the constant term is not zero");
+            }
+
+            // g ← g(1 - log g + f)
+            let mut g = Self::new(vec![StaticModInt::raw(1)]);
+            let mut m = 1;
+            while m < n {
+                m *= 2;
+                let mut h = self.prefix(m) - g.log(m);
+                h.coeffs[0] += 1;
+                g = (g * h).prefix(m);
+            }
+            g.prefix(n)
+        }
+
+        /// Returns $f^k \bmod x^n$.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n \log n)$
+        #[allow(clippy::many_single_char_names)]
+        pub fn pow(&self, k: u64, n: usize) -> Self {
+            if k == 0 {
+                let mut ret = Self::new(vec![StaticModInt::raw(0); n]);
+                if n > 0 {
+                    ret.coeffs[0] = StaticModInt::new(1);
+                }
+                return ret;
+            }
+            let d = match self.coeffs.iter().position(|a| a.val() != 0) {
+                Some(d) => d,
+                None => return Self::new(vec![StaticModInt::raw(0); n]),
+            };
+            let shift = match (d as u64).checked_mul(k) {
+                Some(shift) if shift < n as u64 => shift as usize,
+                _ => return Self::new(vec![StaticModInt::raw(0); n]),
+            };
+
+            // f = c x^d g, where g(0) = 1
+            let c = self.coeffs[d];
+            let ic = c.inv();
+            let g = self.coeffs[d..].iter().map(|&a| a * ic).collect::<Self>();
+            let g = (g.log(n - shift) * StaticModInt::new(k)).exp(n - shift) * c.pow(k);
+            iter::repeat(StaticModInt::raw(0))
+                .take(shift)
+                .chain(g.coeffs)
+                .collect()
+        }
+
+        /// Returns $g$ such that $g^2 \equiv f \pmod{x^n}$ if it exists.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n \log n)$
+        #[allow(clippy::many_single_char_names)]
+        pub fn sqrt(&self, n: usize) -> Option<Self> {
+            let d = match self.coeffs.iter().position(|a| a.val() != 0) {
+                Some(d) => d,
+                None => return Some(Self::new(vec![StaticModInt::raw(0); n])),
+            };
+            if d % 2 == 1 {
+                return None;
+            }
+            if d / 2 >= n {
+                return Some(Self::new(vec![StaticModInt::raw(0); n]));
+            }
+            let len = n - d / 2;
+
+            // f = c x^d g, where g(0) = 1
+            let c = self.coeffs[d];
+            let s = c.sqrt()?;
+            let ic = c.inv();
+            let g = self.coeffs[d..].iter().map(|&a| a * ic).collect::<Self>();
+
+            // h ← (h + g / h) / 2
+            let inv2 = StaticModInt::new(2).inv();
+            let mut h = Self::new(vec![StaticModInt::raw(1)]);
+            let mut m = 1;
+            while m < len {
+                m *= 2;
+                h = ((g.prefix(m) * h.inv(m)).prefix(m) + h) * inv2;
+            }
+            let h = h.prefix(len) * s;
+            Some(
+                iter::repeat(StaticModInt::raw(0))
+                    .take(d / 2)
+                    .chain(h.coeffs)
+                    .collect(),
+            )
+        }
+
+        /// Returns the quotient and the remainder of the polynomial division.
+        ///
+        /// Trailing zeros of the remainder are removed.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `rhs` is zero.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n \log n)$
+        #[allow(clippy::many_single_char_names)]
+        pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+            let b = rhs.clone().normalized();
+            if b.is_empty() {
+                panic!("attempt to divide by zero");
+            }
+            let a = self.clone().normalized();
+            if a.len() < b.len() {
+                return (Self::default(), a);
+            }
+
+            // rev(q) = rev(a) / rev(b) mod x^k
+            let k = a.len() - b.len() + 1;
+            let ra = a.coeffs.iter().rev().take(k).copied().collect::<Self>();
+            let rb = b.coeffs.iter().rev().copied().collect::<Self>();
+            let mut q = (ra * rb.inv(k)).prefix(k);
+            q.coeffs.reverse();
+            let mut r = a - &q * &b;
+            r.truncate(b.len() - 1);
+            (q, r.normalized())
+        }
+
+        fn normalized(mut self) -> Self {
+            while self.coeffs.last().map_or(false, |a| a.val() == 0) {
+                self.coeffs.pop();
+            }
+            self
+        }
+    }
+
+    /// Evaluates the polynomial `f` at each point of `xs`.
+    ///
+    /// The remainders of `f` are computed down a subproduct tree of $\prod_i (x - x_i)$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(|f| \log |f| + k \log^2 k)$ where $k = |xs|$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{fps, ModInt998244353 as Mint};
+    ///
+    /// // 1 + 2x + 3x^2
+    /// let f = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// let xs = [Mint::new(0), Mint::new(1), Mint::new(2)];
+    /// assert_eq!(
+    ///     vec![Mint::new(1), Mint::new(6), Mint::new(17)],
+    ///     fps::multipoint_eval(&f, &xs),
+    /// );
+    /// ```
+    pub fn multipoint_eval<M: Modulus>(
+        f: &[StaticModInt<M>],
+        xs: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        if xs.is_empty() {
+            return vec![];
+        }
+        let tree = subproduct_tree(xs);
+        let sz = tree.len() / 2;
+        let mut rem = vec![Fps::default(); 2 * sz];
+        rem[1] = Fps::new(f.to_owned()).div_rem(&tree[1]).1;
+        for i in 2..2 * sz {
+            rem[i] = rem[i / 2].div_rem(&tree[i]).1;
+        }
+        rem[sz..sz + xs.len()].iter().map(|r| r.coeff(0)).collect()
+    }
+
+    /// Returns the coefficients of the polynomial $f$ of degree less than $k$ such that $f(x_i) = y_i$.
+    ///
+    /// # Constraints
+    ///
+    /// - $|xs| = |ys| = k$
+    /// - $x_i$ are distinct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths are different or the points are not distinct.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(k \log^2 k)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{fps, ModInt998244353 as Mint};
+    ///
+    /// let xs = [Mint::new(0), Mint::new(1), Mint::new(2)];
+    /// let ys = [Mint::new(1), Mint::new(6), Mint::new(17)];
+    /// assert_eq!(
+    ///     vec![Mint::new(1), Mint::new(2), Mint::new(3)],
+    ///     fps::interpolate(&xs, &ys),
+    /// );
+    /// ```
+    pub fn interpolate<M: Modulus>(
+        xs: &[StaticModInt<M>],
+        ys: &[StaticModInt<M>],
+    ) -> Vec<StaticModInt<M>> {
+        assert_eq!(xs.len(), ys.len(), "the lengths are different");
+        let k = xs.len();
+        if k == 0 {
+            return vec![];
+        }
+        let tree = subproduct_tree(xs);
+        let sz = tree.len() / 2;
+
+        // f = Σ_i y_i / g'(x_i) Π_{j ≠ i} (x - x_j), where g = Π_i (x - x_i)
+        let mut w = multipoint_eval(tree[1].derivative().coeffs(), xs);
+        if w.iter().any(|w| w.val() == 0) {
+            panic!("the points are not distinct");
+        }
+        modint::batch_inv(&mut w);
+        let mut vals = vec![Fps::default(); 2 * sz];
+        for (i, (&w, &y)) in w.iter().zip(ys).enumerate() {
+            vals[sz + i] = Fps::new(vec![w * y]);
+        }
+        for i in (1..sz).rev() {
+            vals[i] = &vals[2 * i] * &tree[2 * i + 1] + &vals[2 * i + 1] * &tree[2 * i];
+        }
+        vals[1].prefix(k).into_coeffs()
+    }
+
+    /// Returns the coefficients of $f(x + c)$.
+    ///
+    /// # Constraints
+    ///
+    /// - $|f| <$ `M::VALUE`
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{fps, ModInt998244353 as Mint};
+    ///
+    /// // (x + 1)^2 = 1 + 2x + x^2
+    /// let f = [Mint::new(0), Mint::new(0), Mint::new(1)];
+    /// assert_eq!(
+    ///     vec![Mint::new(1), Mint::new(2), Mint::new(1)],
+    ///     fps::polynomial_taylor_shift(&f, Mint::new(1)),
+    /// );
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn polynomial_taylor_shift<M: Modulus>(
+        f: &[StaticModInt<M>],
+        c: StaticModInt<M>,
+    ) -> Vec<StaticModInt<M>> {
+        let n = f.len();
+        if n == 0 {
+            return vec![];
+        }
+        let mut binomial = Binomial::<StaticModInt<M>>::new(n);
+
+        // g_k k! = Σ_i (f_i i!) (c^(i - k) / (i - k)!)
+        let a = f
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, &f)| f * binomial.fact(i))
+            .collect::<Vec<_>>();
+        let mut pow = StaticModInt::new(1);
+        let b = (0..n)
+            .map(|j| {
+                let ret = pow * binomial.inv_fact(j);
+                pow *= c;
+                ret
+            })
+            .collect::<Vec<_>>();
+        let mut g = convolution::convolution_truncated(&a, &b, n);
+        g.reverse();
+        for (k, g) in g.iter_mut().enumerate() {
+            *g *= binomial.inv_fact(k);
+        }
+        g
+    }
+
+    /// Evaluates the polynomial `f` at $a r^i$ for $0 \leq i < m$ with the chirp z-transform.
+    ///
+    /// # Complexity
+    ///
+    /// - $O((|f| + m) \log (|f| + m))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{fps, ModInt998244353 as Mint};
+    ///
+    /// // 1 + 2x + 3x^2 at 1, 2, 4
+    /// let f = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// assert_eq!(
+    ///     vec![Mint::new(6), Mint::new(17), Mint::new(57)],
+    ///     fps::evaluate_geometric(&f, Mint::new(1), Mint::new(2), 3),
+    /// );
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn evaluate_geometric<M: Modulus>(
+        f: &[StaticModInt<M>],
+        a: StaticModInt<M>,
+        r: StaticModInt<M>,
+        m: usize,
+    ) -> Vec<StaticModInt<M>> {
+        let n = f.len();
+        if n == 0 || m == 0 {
+            return vec![StaticModInt::raw(0); m];
+        }
+        let eval = |x: StaticModInt<M>| {
+            f.iter()
+                .rev()
+                .fold(StaticModInt::raw(0), |acc, &f| acc * x + f)
+        };
+        if r.val() == 0 {
+            let mut ret = vec![f[0]; m];
+            ret[0] = eval(a);
+            return ret;
+        }
+
+        // ij = C(i + j, 2) - C(i, 2) - C(j, 2)
+        let (mut pw, mut ipw) = (vec![StaticModInt::new(1)], vec![StaticModInt::new(1)]);
+        let (mut rt, mut irt) = (StaticModInt::new(1), StaticModInt::new(1));
+        let ir = r.inv();
+        for _ in 1..n + m - 1 {
+            pw.push(*pw.last().unwrap() * rt);
+            ipw.push(*ipw.last().unwrap() * irt);
+            rt *= r;
+            irt *= ir;
+        }
+        let mut ak = StaticModInt::new(1);
+        let mut u = f
+            .iter()
+            .zip(&ipw)
+            .map(|(&f, &ipw)| {
+                let ret = f * ak * ipw;
+                ak *= a;
+                ret
+            })
+            .collect::<Vec<_>>();
+        u.reverse();
+        convolution::middle_product(&pw, &u)
+            .into_iter()
+            .zip(&ipw)
+            .map(|(c, &ipw)| c * ipw)
+            .collect()
+    }
+
+    /// Finds the shortest linear recurrence $a_i = \sum_{j = 1}^d c_j a_{i - j}$ ($d \leq i < |a|$) satisfied by `a`
+    /// with the Berlekamp–Massey algorithm, and returns $(c_1, \ldots, c_d)$.
+    ///
+    /// If `a` is generated by a linear recurrence of order $d$, the recurrence is found from the first $2d$ terms.
+    ///
+    /// # Constraints
+    ///
+    /// - The modulus of `Z` is a prime.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(|a|^2)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{fps, ModInt1000000007 as Mint};
+    ///
+    /// // a_i = a_{i - 1} + a_{i - 2}
+    /// let a = [1, 1, 2, 3, 5, 8].iter().map(|&a| Mint::new(a)).collect::<Vec<_>>();
+    /// assert_eq!(vec![Mint::new(1), Mint::new(1)], fps::berlekamp_massey(&a));
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn berlekamp_massey<Z: ModIntBase>(a: &[Z]) -> Vec<Z> {
+        // c(x) = 1 - Σ_j c_j x^j, and b(x) is `c` just before the last change of `l`
+        let (mut c, mut b) = (vec![Z::raw(1)], vec![Z::raw(1)]);
+        let (mut l, mut m, mut last) = (0, 1, Z::raw(1));
+        for i in 0..a.len() {
+            let d = (0..=l).fold(Z::raw(0), |d, j| d + c[j] * a[i - j]);
+            if d == Z::raw(0) {
+                m += 1;
+                continue;
+            }
+            let coef = d / last;
+            let t = c.clone();
+            if c.len() < b.len() + m {
+                c.resize(b.len() + m, Z::raw(0));
+            }
+            for (c, &b) in c[m..].iter_mut().zip(&b) {
+                *c -= coef * b;
+            }
+            if 2 * l <= i {
+                l = i + 1 - l;
+                b = t;
+                last = d;
+                m = 1;
+            } else {
+                m += 1;
+            }
+        }
+        c.resize(l + 1, Z::raw(0));
+        c[1..].iter().map(|&c| -c).collect()
+    }
+
+    /// Returns $[x^k] \frac{p(x)}{q(x)}$ with the Bostan–Mori algorithm.
+    ///
+    /// The $k$-th term of a linear recurrence $a_i = \sum_{j = 1}^d c_j a_{i - j}$ is the case of
+    /// $q(x) = 1 - \sum_j c_j x^j$ and $p(x) = a(x) q(x) \bmod x^d$.
+    ///
+    /// # Constraints
+    ///
+    /// - $q_0 \neq 0$
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is empty or $q_0 = 0$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log n \log k)$ where $n = \max(|p|, |q|)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{fps, ModInt998244353 as Mint};
+    ///
+    /// // The Fibonacci numbers F_i = F_{i - 1} + F_{i - 2}, F_0 = 0 and F_1 = 1
+    /// let a = [0, 1, 1, 2, 3, 5].iter().map(|&a| Mint::new(a)).collect::<Vec<_>>();
+    /// let c = fps::berlekamp_massey(&a);
+    /// let q = [Mint::new(1), -c[0], -c[1]];
+    /// let p = [a[0], a[1] - c[0] * a[0]];
+    /// assert_eq!(Mint::new(12_586_269_025u64), fps::kth_term_of_rational(&p, &q, 50));
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn kth_term_of_rational<M: Modulus>(
+        p: &[StaticModInt<M>],
+        q: &[StaticModInt<M>],
+        mut k: u64,
+    ) -> StaticModInt<M> {
+        assert!(
+            q.first().map_or(false, |&q0| q0.val() != 0),
+            "the constant term of `q` must be non-zero"
+        );
+        let (mut p, mut q) = (p.to_owned(), q.to_owned());
+        while k > 0 && !p.is_empty() {
+            // p(x) / q(x) = p(x) q(-x) / (q(x) q(-x)), whose denominator is a polynomial of x^2
+            let q_neg = q
+                .iter()
+                .enumerate()
+                .map(|(i, &q)| if i % 2 == 0 { q } else { -q })
+                .collect::<Vec<_>>();
+            let u = convolution::convolution(&p, &q_neg);
+            let v = convolution::convolution(&q, &q_neg);
+            p = u.into_iter().skip((k % 2) as usize).step_by(2).collect();
+            q = v.into_iter().step_by(2).collect();
+            k /= 2;
+        }
+        match p.first() {
+            Some(&p0) if k == 0 => p0 / q[0],
+            _ => StaticModInt::raw(0),
+        }
+    }
+
+    /// Returns the subproduct tree of $\prod_i (x - x_i)$ as a segment tree whose leaves are padded with $1$.
+    fn subproduct_tree<M: Modulus>(xs: &[StaticModInt<M>]) -> Vec<Fps<M>> {
+        let sz = xs.len().next_power_of_two();
+        let mut tree = vec![Fps::new(vec![StaticModInt::new(1)]); 2 * sz];
+        for (i, &x) in xs.iter().enumerate() {
+            tree[sz + i] = Fps::new(vec![-x, StaticModInt::new(1)]);
+        }
+        for i in (1..sz).rev() {
+            tree[i] = &tree[2 * i] * &tree[2 * i + 1];
+        }
+        tree
+    }
+
+    /// Returns the inverses of `0..=n`, where the inverse of `0` is set to `0`.
+    fn inverses<M: Modulus>(n: usize) -> Vec<StaticModInt<M>> {
+        let mut inv = vec![StaticModInt::raw(0); n + 1];
+        if n >= 1 {
+            inv[1] = StaticModInt::new(1);
+        }
+        let m = M::VALUE as usize;
+        for i in 2..=n {
+            inv[i] = -inv[m % i] * StaticModInt::new(m / i);
+        }
+        inv
+    }
+
+    impl<M: Modulus> Default for Fps<M> {
+        #[inline]
+        fn default() -> Self {
+            Self::new(vec![])
+        }
+    }
+
+    impl<M: Modulus> fmt::Debug for Fps<M> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(&self.coeffs, f)
+        }
+    }
+
+    impl<M: Modulus> From<Vec<StaticModInt<M>>> for Fps<M> {
+        #[inline]
+        fn from(coeffs: Vec<StaticModInt<M>>) -> Self {
+            Self::new(coeffs)
+        }
+    }
+
+    impl<M: Modulus> FromIterator<StaticModInt<M>> for Fps<M> {
+        #[inline]
+        fn from_iter<T: IntoIterator<Item = StaticModInt<M>>>(iter: T) -> Self {
+            Self::new(iter.into_iter().collect())
+        }
+    }
+
+    impl<M: Modulus> Neg for Fps<M> {
+        type Output = Self;
+
+        fn neg(mut self) -> Self {
+            for a in &mut self.coeffs {
+                *a = -*a;
+            }
+            self
+        }
+    }
+
+    impl<M: Modulus> Neg for &'_ Fps<M> {
+        type Output = Fps<M>;
+
+        #[inline]
+        fn neg(self) -> Fps<M> {
+            -self.clone()
+        }
+    }
+
+    impl<M: Modulus> Mul<StaticModInt<M>> for Fps<M> {
+        type Output = Self;
+
+        #[inline]
+        fn mul(mut self, rhs: StaticModInt<M>) -> Self {
+            self *= rhs;
+            self
+        }
+    }
+
+    impl<M: Modulus> MulAssign<StaticModInt<M>> for Fps<M> {
+        fn mul_assign(&mut self, rhs: StaticModInt<M>) {
+            for a in &mut self.coeffs {
+                *a *= rhs;
+            }
+        }
+    }
+
+    fn add<M: Modulus>(lhs: &Fps<M>, rhs: &Fps<M>) -> Fps<M> {
+        let n = lhs.len().max(rhs.len());
+        (0..n).map(|i| lhs.coeff(i) + rhs.coeff(i)).collect()
+    }
+
+    fn sub<M: Modulus>(lhs: &Fps<M>, rhs: &Fps<M>) -> Fps<M> {
+        let n = lhs.len().max(rhs.len());
+        (0..n).map(|i| lhs.coeff(i) - rhs.coeff(i)).collect()
+    }
+
+    fn mul<M: Modulus>(lhs: &Fps<M>, rhs: &Fps<M>) -> Fps<M> {
+        Fps::new(convolution::convolution(&lhs.coeffs, &rhs.coeffs))
+    }
+
+    macro_rules! impl_bin_ops {
+        ($(<_> $op:ident::$method:ident, $op_assign:ident::$method_assign:ident => $f:ident;)*) => {
+            $(
+                impl<M: Modulus> $op<&'_ Fps<M>> for &'_ Fps<M> {
+                    type Output = Fps<M>;
+
+                    #[inline]
+                    fn $method(self, rhs: &Fps<M>) -> Fps<M> {
+                        $f(self, rhs)
+                    }
+                }
+
+                impl<M: Modulus> $op<Fps<M>> for &'_ Fps<M> {
+                    type Output = Fps<M>;
+
+                    #[inline]
+                    fn $method(self, rhs: Fps<M>) -> Fps<M> {
+                        $f(self, &rhs)
+                    }
+                }
+
+                impl<M: Modulus> $op<&'_ Fps<M>> for Fps<M> {
+                    type Output = Fps<M>;
+
+                    #[inline]
+                    fn $method(self, rhs: &Fps<M>) -> Fps<M> {
+                        $f(&self, rhs)
+                    }
+                }
+
+                impl<M: Modulus> $op<Fps<M>> for Fps<M> {
+                    type Output = Fps<M>;
+
+                    #[inline]
+                    fn $method(self, rhs: Fps<M>) -> Fps<M> {
+                        $f(&self, &rhs)
+                    }
+                }
+
+                impl<M: Modulus> $op_assign<&'_ Fps<M>> for Fps<M> {
+                    #[inline]
+                    fn $method_assign(&mut self, rhs: &Fps<M>) {
+                        *self = $f(self, rhs);
+                    }
+                }
+
+                impl<M: Modulus> $op_assign<Fps<M>> for Fps<M> {
+                    #[inline]
+                    fn $method_assign(&mut self, rhs: Fps<M>) {
+                        *self = $f(self, &rhs);
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_bin_ops! {
+        <_> Add::add, AddAssign::add_assign => add;
+        <_> Sub::sub, SubAssign::sub_assign => sub;
+        <_> Mul::mul, MulAssign::mul_assign => mul;
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::modint::{Mod998244353, ModInt1000000007, ModInt998244353 as Mint};
+        use super::Fps;
+        use rand::{rngs::ThreadRng, Rng as _};
+
+        #[test]
+        fn derivative_and_integral() {
+            let f = fps(&[1, 2, 3, 4]);
+            assert_eq!(fps(&[2, 6, 12]), f.derivative());
+            assert_eq!(f.prefix(1) + f.derivative().integral(), f);
+            assert_eq!(Fps::default(), Fps::<Mod998244353>::default().derivative());
+            assert_eq!(fps(&[0]), Fps::<Mod998244353>::default().integral());
+        }
+
+        #[test]
+        fn inv() {
+            let mut rng = rand::thread_rng();
+            for &n in &[0, 1, 2, 3, 10, 64, 100, 1000] {
+                let mut f = gen(&mut rng, n + 5);
+                f.coeffs_mut()[0] = Mint::new(rng.gen_range(1, 998_244_353));
+                let g = f.inv(n);
+                assert_eq!(n, g.len());
+                assert_eq!(unit(n), (&f * &g).prefix(n));
+            }
+            assert_eq!(fps(&[1, 1, 1, 1, 1]), fps(&[1, -1]).inv(5));
+        }
+
+        #[test]
+        #[should_panic]
+        fn inv_of_zero_constant() {
+            fps(&[0, 1]).inv(3);
+        }
+
+        #[test]
+        fn log_and_exp() {
+            let mut rng = rand::thread_rng();
+            for &n in &[1, 2, 3, 10, 64, 100, 1000] {
+                let mut f = gen(&mut rng, n);
+                f.coeffs_mut()[0] = Mint::new(1);
+                assert_eq!(f, f.log(n).exp(n));
+
+                let mut f = gen(&mut rng, n);
+                f.coeffs_mut()[0] = Mint::new(0);
+                assert_eq!(f, f.exp(n).log(n));
+            }
+            assert_eq!(fps(&[]), fps(&[1]).log(0));
+            assert_eq!(fps(&[]), fps(&[]).exp(0));
+
+            // exp(x) = Σ x^k / k!
+            let mut fact = Mint::new(1);
+            let e = fps(&[0, 1]).exp(10);
+            for k in 0..10 {
+                if k > 0 {
+                    fact *= Mint::new(k);
+                }
+                assert_eq!(fact.inv(), e.coeff(k));
+            }
+        }
+
+        #[test]
+        fn pow() {
+            let mut rng = rand::thread_rng();
+            for &(len, zeros, n) in &[(5, 0, 10), (5, 1, 10), (5, 3, 10), (30, 2, 100), (1, 0, 3)] {
+                let mut f = gen(&mut rng, len);
+                for a in &mut f.coeffs_mut()[..zeros] {
+                    *a = Mint::new(0);
+                }
+                let mut expected = unit(n);
+                for k in 0..6 {
+                    assert_eq!(expected, f.pow(k, n));
+                    expected = (&expected * &f).prefix(n);
+                }
+            }
+            assert_eq!(fps(&[0, 0, 0, 0]), fps(&[0, 1]).pow(4, 4));
+            assert_eq!(fps(&[0, 0, 0, 0]), fps(&[0, 1]).pow(u64::max_value(), 4));
+            assert_eq!(fps(&[0, 0]), fps(&[]).pow(2, 2));
+            assert_eq!(fps(&[1, 0]), fps(&[]).pow(0, 2));
+            // (1 + x)^p = 1 + x^p
+            assert_eq!(fps(&[1, 1, 0]), fps(&[1, 1]).pow(998_244_353 + 1, 3));
+        }
+
+        #[test]
+        fn sqrt() {
+            let mut rng = rand::thread_rng();
+            for &(len, zeros, n) in &[(5, 0, 10), (5, 2, 10), (50, 4, 100), (1, 0, 1)] {
+                let mut g = gen(&mut rng, len);
+                for a in &mut g.coeffs_mut()[..zeros] {
+                    *a = Mint::new(0);
+                }
+                g.coeffs_mut()[zeros] = Mint::new(rng.gen_range(1, 998_244_353));
+                let f = (&g * &g).prefix(n);
+                let h = f.sqrt(n).unwrap();
+                assert_eq!(n, h.len());
+                assert_eq!(f, (&h * &h).prefix(n));
+            }
+            assert_eq!(None, fps(&[0, 1]).sqrt(3));
+            // 3 is a quadratic non-residue modulo 998244353
+            assert_eq!(None, fps(&[3]).sqrt(3));
+            assert_eq!(Some(fps(&[0, 0, 0])), fps(&[]).sqrt(3));
+            assert_eq!(Some(fps(&[0, 0])), fps(&[0, 0, 0, 0, 4]).sqrt(2));
+        }
+
+        #[test]
+        fn div_rem() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(10, 3), (3, 10), (100, 100), (1000, 300), (5, 1)] {
+                let a = gen(&mut rng, n);
+                let mut b = gen(&mut rng, m);
+                b.coeffs_mut()[m - 1] = Mint::new(rng.gen_range(1, 998_244_353));
+                let (q, r) = a.div_rem(&b);
+                assert!(r.len() < m);
+                assert_eq!(a.clone().normalized(), (&q * &b + &r).normalized());
+            }
+            let (q, r) = fps(&[-1, 0, 1]).div_rem(&fps(&[-1, 1, 0]));
+            assert_eq!((fps(&[1, 1]), fps(&[])), (q, r));
+        }
+
+        #[test]
+        #[should_panic]
+        fn div_by_zero() {
+            fps(&[1, 2]).div_rem(&fps(&[0, 0]));
+        }
+
+        #[test]
+        fn multipoint_eval_and_interpolate() {
+            let mut rng = rand::thread_rng();
+            for &(n, k) in &[
+                (0, 3),
+                (1, 1),
+                (5, 1),
+                (10, 10),
+                (100, 37),
+                (300, 1000),
+                (1000, 300),
+            ] {
+                let f = gen(&mut rng, n);
+                let xs = gen(&mut rng, k).into_coeffs();
+                let expected = xs.iter().map(|&x| horner(&f, x)).collect::<Vec<_>>();
+                assert_eq!(expected, super::multipoint_eval(f.coeffs(), &xs));
+
+                if n <= k {
+                    assert_eq!(
+                        f.prefix(k).into_coeffs(),
+                        super::interpolate(&xs, &expected)
+                    );
+                }
+            }
+            assert!(super::multipoint_eval(fps(&[1, 2]).coeffs(), &[]).is_empty());
+            assert!(super::interpolate::<Mod998244353>(&[], &[]).is_empty());
+        }
+
+        #[test]
+        #[should_panic]
+        fn interpolate_with_duplicate_points() {
+            let xs = fps(&[1, 2, 1]).into_coeffs();
+            super::interpolate(&xs, &xs);
+        }
+
+        #[test]
+        fn polynomial_taylor_shift() {
+            let mut rng = rand::thread_rng();
+            for &n in &[0, 1, 2, 10, 100, 500] {
+                let f = gen(&mut rng, n);
+                let c = Mint::new(rng.gen_range(0, 998_244_353));
+                let g = Fps::new(super::polynomial_taylor_shift(f.coeffs(), c));
+                assert_eq!(n, g.len());
+                for _ in 0..10 {
+                    let x = Mint::new(rng.gen_range(0, 998_244_353));
+                    assert_eq!(horner(&f, x + c), horner(&g, x));
+                }
+            }
+        }
+
+        #[test]
+        fn evaluate_geometric() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[
+                (0, 3),
+                (3, 0),
+                (1, 1),
+                (10, 10),
+                (100, 37),
+                (37, 100),
+                (500, 1000),
+            ] {
+                let f = gen(&mut rng, n);
+                for &(a, r) in &[
+                    (
+                        Mint::new(rng.gen_range(0, 998_244_353)),
+                        Mint::new(rng.gen_range(1, 998_244_353)),
+                    ),
+                    (Mint::new(0), Mint::new(3)),
+                    (Mint::new(5), Mint::new(0)),
+                    (Mint::new(5), Mint::new(1)),
+                ] {
+                    let expected = (0..m)
+                        .map(|i| horner(&f, a * r.pow(i as u64)))
+                        .collect::<Vec<_>>();
+                    assert_eq!(expected, super::evaluate_geometric(f.coeffs(), a, r, m));
+                }
+            }
+        }
+
+        #[test]
+        fn berlekamp_massey() {
+            let mut rng = rand::thread_rng();
+            for d in 0..30 {
+                let c = gen(&mut rng, d).into_coeffs();
+                let mut a = gen(&mut rng, d).into_coeffs();
+                for i in d..2 * d + 10 {
+                    let x = (1..=d).map(|j| c[j - 1] * a[i - j]).sum();
+                    a.push(x);
+                }
+                let found = super::berlekamp_massey(&a);
+                assert!(found.len() <= d);
+                for i in found.len()..a.len() {
+                    let x = (1..=found.len()).map(|j| found[j - 1] * a[i - j]).sum();
+                    assert_eq!(a[i], x);
+                }
+            }
+
+            let a = [0, 0, 0, 1, 2, 4, 8]
+                .iter()
+                .map(|&a| ModInt1000000007::new(a))
+                .collect::<Vec<_>>();
+            assert_eq!(4, super::berlekamp_massey(&a).len());
+            assert!(super::berlekamp_massey::<Mint>(&[]).is_empty());
+            assert!(super::berlekamp_massey(&[Mint::new(0); 5]).is_empty());
+        }
+
+        #[test]
+        fn kth_term_of_rational() {
+            let mut rng = rand::thread_rng();
+            for &(n, m) in &[(0, 1), (1, 1), (3, 5), (5, 3), (50, 70)] {
+                let p = gen(&mut rng, n);
+                let mut q = gen(&mut rng, m);
+                q.coeffs_mut()[0] = Mint::new(1);
+                let f = &p * q.inv(300);
+                for k in 0..300 {
+                    assert_eq!(
+                        f.coeff(k),
+                        super::kth_term_of_rational(p.coeffs(), q.coeffs(), k as _)
+                    );
+                }
+            }
+
+            let k = 1_000_000_000_000_000_000;
+            // 1 / (1 - x)^2 = Σ_k (k + 1) x^k
+            let (p, q) = (fps(&[1]), fps(&[1, -2, 1]));
+            assert_eq!(
+                Mint::new(k + 1),
+                super::kth_term_of_rational(p.coeffs(), q.coeffs(), k)
+            );
+            // 1 / (1 - 2x) = Σ_k 2^k x^k
+            let (p, q) = (fps(&[1]), fps(&[1, -2]));
+            assert_eq!(
+                Mint::new(2).pow(k),
+                super::kth_term_of_rational(p.coeffs(), q.coeffs(), k)
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn kth_term_of_rational_with_zero_constant_term() {
+            super::kth_term_of_rational(fps(&[1]).coeffs(), fps(&[0, 1]).coeffs(), 1);
+        }
+
+        fn horner(f: &Fps<Mod998244353>, x: Mint) -> Mint {
+            f.coeffs()
+                .iter()
+                .rev()
+                .fold(Mint::new(0), |acc, &f| acc * x + f)
+        }
+
+        fn fps(coeffs: &[i64]) -> Fps<Mod998244353> {
+            coeffs.iter().map(|&a| Mint::new(a)).collect()
+        }
+
+        fn unit(n: usize) -> Fps<Mod998244353> {
+            Fps::new(vec![Mint::new(1)]).prefix(n)
+        }
+
+        fn gen(rng: &mut ThreadRng, n: usize) -> Fps<Mod998244353> {
+            (0..n)
+                .map(|_| Mint::new(rng.gen_range(0, 998_244_353)))
+                .collect()
+        }
+    }
+}
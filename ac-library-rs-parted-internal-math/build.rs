@@ -0,0 +1,35 @@
+// The build script of `ac-library-rs-parted-internal-math` and `ac-library-rs-parted-modint`.
+use std::{env, process::Command, str};
+
+fn main() {
+    let minor = match rustc_minor_version() {
+        Some(minor) => minor,
+        None => return,
+    };
+
+    if minor >= 80 {
+        println!("cargo:rustc-check-cfg=cfg(const_fn_control_flow)");
+        println!("cargo:rustc-check-cfg=cfg(const_fn_trait_bound)");
+    }
+
+    // `if`, `match` and loops in `const fn`s are stable since Rust 1.46.
+    if minor >= 46 {
+        println!("cargo:rustc-cfg=const_fn_control_flow");
+    }
+
+    // `const fn`s with trait bounds on generic parameters are stable since Rust 1.61.
+    if minor >= 61 {
+        println!("cargo:rustc-cfg=const_fn_trait_bound");
+    }
+}
+
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = str::from_utf8(&output.stdout).ok()?;
+    let mut pieces = version.split('.');
+    if pieces.next() != Some("rustc 1") {
+        return None;
+    }
+    pieces.next()?.parse().ok()
+}
@@ -1,4 +1,4 @@
-// This code was expanded by `xtask`.
+// This code was expanded by `xtask` and has been extended by hand since. `xtask` no longer regenerates it.
 
 pub use self::internal_math::*;
 
@@ -7,18 +7,32 @@ mod internal_math {
     #![allow(dead_code)]
     use std::mem::swap;
 
-    /// # Arguments
-    /// * `m` `1 <= m`
-    ///
-    /// # Returns
-    /// x mod m
-    /* const */
-    pub fn safe_mod(mut x: i64, m: i64) -> i64 {
-        x %= m;
-        if x < 0 {
-            x += m;
+    // `const fn` if `build.rs` sets `cfg(const_fn_control_flow)`
+    macro_rules! const_fn {
+        ($(#[$attr:meta])* pub fn $($rest:tt)*) => {
+            #[cfg(const_fn_control_flow)]
+            $(#[$attr])*
+            pub const fn $($rest)*
+
+            #[cfg(not(const_fn_control_flow))]
+            $(#[$attr])*
+            pub fn $($rest)*
+        };
+    }
+
+    const_fn! {
+        /// # Arguments
+        /// * `m` `1 <= m`
+        ///
+        /// # Returns
+        /// x mod m
+        pub fn safe_mod(mut x: i64, m: i64) -> i64 {
+            x %= m;
+            if x < 0 {
+                x += m;
+            }
+            x
         }
-        x
     }
 
     /// Fast modular by barrett reduction
@@ -88,62 +102,121 @@ mod internal_math {
         v
     }
 
-    /// # Parameters
-    /// * `n` `0 <= n`
-    /// * `m` `1 <= m`
+    /// Calculates `a * b % m` for 64-bit `m`.
     ///
-    /// # Returns
-    /// `(x ** n) % m`
-    /* const */
+    /// * `a` `0 <= a < m`
+    /// * `b` `0 <= b < m`
+    /// * `m` `1 <= m`
+    /// * `im` = floor((2^128 - 1) / `m`)
     #[allow(clippy::many_single_char_names)]
-    pub fn pow_mod(x: i64, mut n: i64, m: i32) -> i64 {
-        if m == 1 {
-            return 0;
+    pub fn mul_mod_u64(a: u64, b: u64, m: u64, im: u128) -> u64 {
+        // let z = a*b < m^2 <= 2^128
+        // im * m = 2^128 - 1 - r (0 <= r < m)
+        // z * im / 2^128 = z / m - z * (r + 1) / (m * 2^128) > z / m - 1
+        // floor(z * im / 2^128) is floor(z / m), floor(z / m) - 1 or floor(z / m) - 2
+        let z = u128::from(a) * u128::from(b);
+        let x = mul_high_u128(z, im);
+        let mut v = z - x * u128::from(m);
+        while v >= u128::from(m) {
+            v -= u128::from(m);
         }
-        let _m = m as u32;
-        let mut r: u64 = 1;
-        let mut y: u64 = safe_mod(x, m as i64) as u64;
-        while n != 0 {
-            if (n & 1) > 0 {
-                r = (r * y) % (_m as u64);
+        v as u64
+    }
+
+    /// Returns the upper 128 bits of `a * b`.
+    fn mul_high_u128(a: u128, b: u128) -> u128 {
+        const MASK: u128 = (1 << 64) - 1;
+        let (a_hi, a_lo) = (a >> 64, a & MASK);
+        let (b_hi, b_lo) = (b >> 64, b & MASK);
+        let lo_lo = a_lo * b_lo;
+        let hi_lo = a_hi * b_lo;
+        let lo_hi = a_lo * b_hi;
+        let hi_hi = a_hi * b_hi;
+        let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+        hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64)
+    }
+
+    const_fn! {
+        /// # Parameters
+        /// * `n` `0 <= n`
+        /// * `m` `1 <= m`
+        ///
+        /// # Returns
+        /// `(x ** n) % m`
+        #[allow(clippy::many_single_char_names)]
+        pub fn pow_mod(x: i64, mut n: i64, m: i32) -> i64 {
+            if m == 1 {
+                return 0;
+            }
+            let _m = m as u32;
+            let mut r: u64 = 1;
+            let mut y: u64 = safe_mod(x, m as i64) as u64;
+            while n != 0 {
+                if (n & 1) > 0 {
+                    r = (r * y) % (_m as u64);
+                }
+                y = (y * y) % (_m as u64);
+                n >>= 1;
             }
-            y = (y * y) % (_m as u64);
-            n >>= 1;
+            r as i64
         }
-        r as i64
     }
 
-    /// Reference:
-    /// M. Forisek and J. Jancina,
-    /// Fast Primality Testing for Integers That Fit into a Machine Word
-    ///
-    /// # Parameters
-    /// * `n` `0 <= n`
-    /* const */
-    pub fn is_prime(n: i32) -> bool {
-        let n = n as i64;
-        match n {
-            _ if n <= 1 => return false,
-            2 | 7 | 61 => return true,
-            _ if n % 2 == 0 => return false,
-            _ => {}
-        }
-        let mut d = n - 1;
-        while d % 2 == 0 {
-            d /= 2;
+    const_fn! {
+        /// Reference:
+        /// M. Forisek and J. Jancina,
+        /// Fast Primality Testing for Integers That Fit into a Machine Word
+        ///
+        /// # Parameters
+        /// * `n` `0 <= n`
+        pub fn is_prime(n: i32) -> bool {
+            0 <= n && is_prime_u32(n as u32)
         }
-        for &a in &[2, 7, 61] {
-            let mut t = d;
-            let mut y = pow_mod(a, t, n as i32);
-            while t != n - 1 && y != 1 && y != n - 1 {
-                y = y * y % n;
-                t <<= 1;
+    }
+
+    const_fn! {
+        /// [`is_prime`] for the whole range of `u32`.
+        ///
+        /// The bases `2`, `7` and `61` are enough for every `n < 4759123141`, and products are taken in `u64`.
+        ///
+        /// [`is_prime`]: ./fn.is_prime.html
+        pub fn is_prime_u32(n: u32) -> bool {
+            let n = n as u64;
+            match n {
+                _ if n <= 1 => return false,
+                2 | 7 | 61 => return true,
+                _ if n % 2 == 0 => return false,
+                _ => {}
             }
-            if y != n - 1 && t % 2 == 0 {
-                return false;
+            let mut d = n - 1;
+            while d % 2 == 0 {
+                d /= 2;
+            }
+            // `for` loops are not allowed in `const fn`s.
+            let bases = [2, 7, 61];
+            let mut i = 0;
+            while i < bases.len() {
+                let mut t = d;
+                // `pow_mod` takes `m` as `i32`
+                let (mut y, mut a, mut e) = (1, bases[i], t);
+                while e != 0 {
+                    if e & 1 == 1 {
+                        y = y * a % n;
+                    }
+                    a = a * a % n;
+                    e >>= 1;
+                }
+                while t != n - 1 && y != 1 && y != n - 1 {
+                    y = y * y % n;
+                    t <<= 1;
+                }
+                if y != n - 1 && t % 2 == 0 {
+                    return false;
+                }
+                i += 1;
             }
+            true
         }
-        true
     }
 
     // omitted
@@ -192,6 +265,42 @@ mod internal_math {
         (s, m0)
     }
 
+    /// 64-bit version of [`inv_gcd`].
+    ///
+    /// # Parameters
+    /// * `b` `1 <= b`
+    ///
+    /// # Returns
+    /// (g, x) s.t. g = gcd(a, b), xa = g (mod b), 0 <= x < b/g
+    ///
+    /// [`inv_gcd`]: ./fn.inv_gcd.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn inv_gcd_u64(a: u64, b: u64) -> (u64, u64) {
+        let a = a % b;
+        if a == 0 {
+            return (b, 0);
+        }
+
+        // Same contracts as `inv_gcd`. |m0|, |m1| <= b < 2^64 so they fit in `i128`.
+        let mut s = b;
+        let mut t = a;
+        let mut m0 = 0i128;
+        let mut m1 = 1i128;
+
+        while t != 0 {
+            let u = s / t;
+            s -= t * u;
+            m0 -= m1 * i128::from(u);
+
+            swap(&mut s, &mut t);
+            swap(&mut m0, &mut m1);
+        }
+        if m0 < 0 {
+            m0 += i128::from(b / s);
+        }
+        (s, m0 as u64)
+    }
+
     /// Compile time (currently not) primitive root
     /// @param m must be prime
     /// @return primitive root (and minimum in now)
@@ -244,7 +353,10 @@ mod internal_math {
     mod tests {
         #![allow(clippy::unreadable_literal)]
         #![allow(clippy::cognitive_complexity)]
-        use super::{inv_gcd, is_prime, pow_mod, primitive_root, safe_mod, Barrett};
+        use super::{
+            inv_gcd, inv_gcd_u64, is_prime, is_prime_u32, mul_mod_u64, pow_mod, primitive_root,
+            safe_mod, Barrett,
+        };
         use std::collections::HashSet;
 
         #[test]
@@ -287,6 +399,32 @@ mod internal_math {
             assert_eq!(b.mul(1073741824, 2147483645), 2147483646);
         }
 
+        #[test]
+        fn test_mul_mod_u64() {
+            for &m in &[
+                1,
+                2,
+                7,
+                998244353,
+                (1 << 61) - 1,
+                (1 << 63) + 1,
+                u64::max_value() - 58,
+                u64::max_value(),
+            ] {
+                let im = u128::max_value() / u128::from(m);
+                for &a in &[0, 1, 2, m / 3, m / 2, m.saturating_sub(2), m - 1] {
+                    for &b in &[0, 1, 3, m / 5, m / 2, m - 1] {
+                        if a < m && b < m {
+                            assert_eq!(
+                                (u128::from(a) * u128::from(b) % u128::from(m)) as u64,
+                                mul_mod_u64(a, b, m, im),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
         #[test]
         fn test_pow_mod() {
             assert_eq!(pow_mod(0, 0, 1), 0);
@@ -377,6 +515,17 @@ mod internal_math {
             assert!(is_prime(i32::max_value()));
         }
 
+        #[test]
+        fn test_is_prime_u32() {
+            assert!(is_prime_u32(i32::max_value() as u32));
+            assert!(!is_prime_u32(1 << 31));
+            assert!(!is_prime_u32(2_147_483_649));
+            assert!(is_prime_u32(4_294_967_291));
+            assert!(!is_prime_u32(4_294_967_295));
+            assert!(!is_prime_u32(4_294_967_293));
+            assert!(!is_prime_u32(3_215_031_751)); // strong pseudoprime to bases 2, 3, 5, 7
+        }
+
         #[test]
         fn test_is_prime_sieve() {
             let n = 1_000_000;
@@ -417,6 +566,28 @@ mod internal_math {
             }
         }
 
+        #[test]
+        fn test_inv_gcd_u64() {
+            for &(a, b, g) in &[
+                (0, 1, 1),
+                (0, 4, 4),
+                (2, 3, 1),
+                (4, 6, 2),
+                (57, 81, 3),
+                (12345, 67890, 15),
+                (u64::max_value(), u64::max_value(), u64::max_value()),
+                (u64::max_value() - 1, u64::max_value(), 1),
+                (3, (1 << 63) + 1, 3),
+                (2, u64::max_value(), 1),
+            ] {
+                let (g_, x) = inv_gcd_u64(a, b);
+                assert_eq!(g, g_);
+                assert!(x < b / g);
+                let b_ = u128::from(b);
+                assert_eq!(u128::from(x) * u128::from(a) % b_, u128::from(g) % b_);
+            }
+        }
+
         #[test]
         fn test_primitive_root() {
             for &p in &[
@@ -1,16 +1,18 @@
-// This code was expanded by `xtask`.
+// This code was expanded by `xtask` and has been extended by hand since. `xtask` no longer regenerates it.
 
 #![doc = " Number-theoretic algorithms."]
 
 extern crate __acl_internal_math as internal_math;
+extern crate __acl_segtree as segtree;
 
 pub use self::math::*;
 
 mod math {
 
     use super::internal_math;
+    use super::segtree::Monoid;
 
-    use std::mem::swap;
+    use std::{fmt, mem::swap};
 
     /// Returns $x^n \bmod m$.
     ///
@@ -121,6 +123,61 @@ mod math {
     /// assert_eq!(math::crt(&r, &m), (23, 105));
     /// ```
     pub fn crt(r: &[i64], m: &[i64]) -> (i64, i64) {
+        // `m0 * u1` overflows as in the original ACL if lcm(m) is not in `i64`
+        crt_impl(r, m, |m0, u1| Some(m0 * u1)).unwrap_or((0, 0))
+    }
+
+    /// Performs CRT (Chinese Remainder Theorem), reporting the failure as an error.
+    ///
+    /// This is the same as [`crt`] except that it returns an error with [`CrtErrorKind::NoSolution`] if there is no
+    /// solution and with [`CrtErrorKind::Overflow`] if $\text{lcm}(m)$ is not in `i64`.
+    ///
+    /// # Constraints
+    ///
+    /// - $|r| = |m|$
+    /// - $1 \leq m_{\forall i}$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log \text{lcm}(m))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math::{self, CrtErrorKind};
+    ///
+    /// assert_eq!(Ok((23, 105)), math::crt_checked(&[2, 3, 2], &[3, 5, 7]));
+    ///
+    /// let err = math::crt_checked(&[0, 1], &[2, 4]).unwrap_err();
+    /// assert_eq!(&CrtErrorKind::NoSolution, err.kind());
+    ///
+    /// let m = [1_000_000_007, 998_244_353, 1_000_000_009];
+    /// let err = math::crt_checked(&[1, 2, 3], &m).unwrap_err();
+    /// assert_eq!(&CrtErrorKind::Overflow, err.kind());
+    /// ```
+    ///
+    /// [`crt`]: ./fn.crt.html
+    /// [`CrtErrorKind::NoSolution`]: ./enum.CrtErrorKind.html#variant.NoSolution
+    /// [`CrtErrorKind::Overflow`]: ./enum.CrtErrorKind.html#variant.Overflow
+    pub fn crt_checked(r: &[i64], m: &[i64]) -> Result<(i64, i64), CrtError> {
+        crt_impl(r, m, i64::checked_mul).map_err(|kind| CrtError { kind })
+    }
+
+    /// The algorithm of [`crt`] and [`crt_checked`].
+    ///
+    /// `mul(m0, u1)` computes the next modulus and returns `None` if it overflows.
+    ///
+    /// [`crt`]: ./fn.crt.html
+    /// [`crt_checked`]: ./fn.crt_checked.html
+    fn crt_impl(
+        r: &[i64],
+        m: &[i64],
+        mul: impl Fn(i64, i64) -> Option<i64>,
+    ) -> Result<(i64, i64), CrtErrorKind> {
         assert_eq!(r.len(), m.len());
         // Contracts: 0 <= r0 < m0
         let (mut r0, mut m0) = (0, 1);
@@ -133,7 +190,7 @@ mod math {
             }
             if m0 % mi == 0 {
                 if r0 % mi != ri {
-                    return (0, 0);
+                    return Err(CrtErrorKind::NoSolution);
                 }
                 continue;
             }
@@ -151,67 +208,1382 @@ mod math {
             let u1 = mi / g;
             // |ri - r0| < (m0 + mi) <= lcm(m0, mi)
             if (ri - r0) % g != 0 {
-                return (0, 0);
+                return Err(CrtErrorKind::NoSolution);
+            }
+            // the bounds below hold as long as lcm(m0, mi) is in i64
+            let m2 = mul(m0, u1).ok_or(CrtErrorKind::Overflow)?;
+            // u1 * u1 <= mi * mi / g / g <= m0 * mi / g = lcm(m0, mi)
+            let x = (ri - r0) / g % u1 * im % u1;
+
+            // |r0| + |m0 * x|
+            // < m0 + m0 * (u1 - 1)
+            // = m0 + m0 * mi / g - m0
+            // = lcm(m0, mi)
+            r0 += x * m0;
+            m0 = m2; // -> lcm(m0, mi)
+            if r0 < 0 {
+                r0 += m0
+            };
+        }
+
+        Ok((r0, m0))
+    }
+
+    /// Performs CRT (Chinese Remainder Theorem) on `u128`.
+    ///
+    /// This is the same as [`crt_checked`] except that $r$ and $m$ are `u128` and it returns an error with
+    /// [`CrtErrorKind::Overflow`] if $\text{lcm}(m)$ is not in `u128`.
+    ///
+    /// # Constraints
+    ///
+    /// - $|r| = |m|$
+    /// - $1 \leq m_{\forall i}$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n \log^2 \text{lcm}(m))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// let m = [1_000_000_007, 998_244_353, 1_000_000_009];
+    /// let (y, z) = math::crt_u128(&[1, 2, 3], &m).unwrap();
+    /// assert_eq!(1_000_000_007 * 998_244_353 * 1_000_000_009, z);
+    /// assert_eq!([1, 2, 3], [y % m[0], y % m[1], y % m[2]]);
+    /// ```
+    ///
+    /// [`crt_checked`]: ./fn.crt_checked.html
+    /// [`CrtErrorKind::Overflow`]: ./enum.CrtErrorKind.html#variant.Overflow
+    pub fn crt_u128(r: &[u128], m: &[u128]) -> Result<(u128, u128), CrtError> {
+        assert_eq!(r.len(), m.len());
+        // Contracts: 0 <= r0 < m0
+        let (mut r0, mut m0) = (0, 1);
+        for (&ri, &mi) in r.iter().zip(m.iter()) {
+            assert!(1 <= mi);
+            let (mut ri, mut mi) = (ri % mi, mi);
+            if m0 < mi {
+                swap(&mut r0, &mut ri);
+                swap(&mut m0, &mut mi);
+            }
+            if m0 % mi == 0 {
+                if r0 % mi != ri {
+                    return Err(CrtError {
+                        kind: CrtErrorKind::NoSolution,
+                    });
+                }
+                continue;
+            }
+
+            // the same as `crt_checked` with (ri - r0) taken modulo mi
+            let g = gcd_u128(m0, mi);
+            let (u0, u1) = (m0 / g, mi / g);
+            let r0_mod = r0 % mi;
+            let diff = if ri >= r0_mod {
+                ri - r0_mod
+            } else {
+                mi - (r0_mod - ri)
+            };
+            if diff % g != 0 {
+                return Err(CrtError {
+                    kind: CrtErrorKind::NoSolution,
+                });
+            }
+            let m2 = m0.checked_mul(u1).ok_or(CrtError {
+                kind: CrtErrorKind::Overflow,
+            })?;
+            let x = mul_mod_u128(diff / g, inv_mod_u128(u0, u1), u1);
+            // r0 + x * m0 < m0 + (u1 - 1) * m0 = lcm(m0, mi)
+            r0 += x * m0;
+            m0 = m2;
+        }
+
+        Ok((r0, m0))
+    }
+
+    /// Returns $x \bmod t$ for the solution $0 \leq x < \prod m$ of
+    ///
+    /// \\[
+    ///   x \equiv r_i \pmod{m_i}, \forall i \in \\{0, 1, \cdots, n - 1\\}
+    /// \\]
+    ///
+    /// with Garner's algorithm, where $t$ is `target_mod`.
+    ///
+    /// Unlike [`crt`], $\prod m$ does not need to be in `i64`.
+    ///
+    /// # Constraints
+    ///
+    /// - $|r| = |m|$
+    /// - $1 \leq m_{\forall i}$
+    /// - $m$ are pairwise coprime
+    /// - $1 \leq t$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^2 + n \log \max(m))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// // x = 10^20 + 7
+    /// let m = [1_000_000_007, 998_244_353, 1_000_000_009];
+    /// let r = [
+    ///     ((100_000_000_000_000_000_007u128) % 1_000_000_007) as i64,
+    ///     ((100_000_000_000_000_000_007u128) % 998_244_353) as i64,
+    ///     ((100_000_000_000_000_000_007u128) % 1_000_000_009) as i64,
+    /// ];
+    /// assert_eq!(
+    ///     ((100_000_000_000_000_000_007u128) % 1_000_000_000_000) as i64,
+    ///     math::garner(&r, &m, 1_000_000_000_000),
+    /// );
+    /// ```
+    ///
+    /// [`crt`]: ./fn.crt.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn garner(r: &[i64], m: &[i64], target_mod: i64) -> i64 {
+        assert_eq!(r.len(), m.len());
+        assert!(1 <= target_mod);
+        let n = r.len();
+        let mul = |a: i64, b: i64, m: i64| (i128::from(a) * i128::from(b) % i128::from(m)) as i64;
+        // x = t_0 + t_1 m_0 + t_2 m_0 m_1 + ...
+        // coeffs[j] = m_0 m_1 ... m_{i - 1} mod m_j
+        // consts[j] = t_0 + t_1 m_0 + ... + t_{i - 1} m_0 ... m_{i - 2} mod m_j
+        // where m_n = target_mod
+        let mut coeffs = vec![1; n + 1];
+        let mut consts = vec![0; n + 1];
+        for i in 0..n {
+            assert!(1 <= m[i]);
+            let (g, inv) = internal_math::inv_gcd(coeffs[i], m[i]);
+            assert_eq!(1, g, "the moduli must be pairwise coprime");
+            // 0 <= ri, consts[i] < m[i]
+            let ri = internal_math::safe_mod(r[i], m[i]);
+            let diff = internal_math::safe_mod(ri - consts[i], m[i]);
+            let t = mul(diff, inv, m[i]);
+            for j in i + 1..=n {
+                let mj = if j < n { m[j] } else { target_mod };
+                consts[j] = ((i128::from(consts[j]) + i128::from(mul(t, coeffs[j], mj)))
+                    % i128::from(mj)) as i64;
+                coeffs[j] = mul(coeffs[j], m[i], mj);
+            }
+        }
+        consts[n]
+    }
+
+    /// An error which can be returned by [`crt_checked`] and [`crt_u128`].
+    ///
+    /// [`crt_checked`]: ./fn.crt_checked.html
+    /// [`crt_u128`]: ./fn.crt_u128.html
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct CrtError {
+        kind: CrtErrorKind,
+    }
+
+    impl CrtError {
+        /// Returns the detailed cause of CRT failing.
+        #[inline]
+        pub fn kind(&self) -> &CrtErrorKind {
+            &self.kind
+        }
+    }
+
+    impl fmt::Display for CrtError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self.kind {
+                CrtErrorKind::NoSolution => "the system of congruences has no solution",
+                CrtErrorKind::Overflow => "the least common multiple of the moduli overflowed",
+            })
+        }
+    }
+
+    impl std::error::Error for CrtError {}
+
+    /// Enum to store the various types of errors that can cause CRT to fail.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CrtErrorKind {
+        /// The congruences are inconsistent.
+        NoSolution,
+        /// The least common multiple of the moduli does not fit in the integer type.
+        Overflow,
+    }
+
+    /// Returns $\sum_{i = 0}^{n - 1} \lfloor \frac{a \times i + b}{m} \rfloor$.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq n \leq 10^9$
+    /// - $1 \leq m \leq 10^9$
+    /// - $0 \leq a, b \leq m$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied and overflow or division by zero occurred.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log(n + m + a + b))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert_eq!(math::floor_sum(6, 5, 4, 3), 13);
+    /// ```
+    pub fn floor_sum(n: i64, m: i64, mut a: i64, mut b: i64) -> i64 {
+        let mut ans = 0;
+        if a >= m {
+            ans += (n - 1) * n * (a / m) / 2;
+            a %= m;
+        }
+        if b >= m {
+            ans += n * (b / m);
+            b %= m;
+        }
+
+        let y_max = (a * n + b) / m;
+        let x_max = y_max * m - b;
+        if y_max == 0 {
+            return ans;
+        }
+        ans += (n - (x_max + a - 1) / a) * y_max;
+        ans += floor_sum(y_max, a, m, (a - x_max % a) % a);
+        ans
+    }
+
+    /// Returns $\sum_{i = 0}^{n - 1} i \lfloor \frac{a \times i + b}{m} \rfloor$.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq n \leq 10^9$
+    /// - $1 \leq m \leq 10^9$
+    /// - $0 \leq a, b \leq m$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied and overflow or division by zero occurred.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log(n + m + a + b))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// // 0 * 0 + 1 * 1 + 2 * 2 + 3 * 3 + 4 * 3 + 5 * 4
+    /// assert_eq!(math::floor_sum_weighted(6, 5, 4, 3), 46);
+    /// ```
+    pub fn floor_sum_weighted(n: i64, m: i64, a: i64, b: i64) -> i128 {
+        assert!(0 <= n && 1 <= m && 0 <= a && 0 <= b);
+        if n == 0 {
+            return 0;
+        }
+        floor_sums(n as i128 - 1, m.into(), a.into(), b.into()).1
+    }
+
+    /// Returns $\sum_{i = 0}^{n - 1} \lfloor \frac{a \times i + b}{m} \rfloor^2$.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq n \leq 10^9$
+    /// - $1 \leq m \leq 10^9$
+    /// - $0 \leq a, b \leq m$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied and overflow or division by zero occurred.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log(n + m + a + b))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// // 0^2 + 1^2 + 2^2 + 3^2 + 3^2 + 4^2
+    /// assert_eq!(math::floor_sum_squared(6, 5, 4, 3), 39);
+    /// ```
+    pub fn floor_sum_squared(n: i64, m: i64, a: i64, b: i64) -> i128 {
+        assert!(0 <= n && 1 <= m && 0 <= a && 0 <= b);
+        if n == 0 {
+            return 0;
+        }
+        floor_sums(n as i128 - 1, m.into(), a.into(), b.into()).2
+    }
+
+    /// Returns the product of $U^{f(0)} R U^{f(1) - f(0)} R \cdots R U^{f(n) - f(n - 1)}$ in the monoid `M`, where
+    /// $f(i) = \lfloor \frac{a \times i + b}{m} \rfloor$ and $U, R$ are `u` and `r`.
+    ///
+    /// In other words, this walks along the line $y = \frac{a x + b}{m}$ from $x = 0$ to $x = n$, and multiplies
+    /// $U$ every time the walk crosses $y = 1, 2, \ldots$ and $R$ every time it crosses $x = 1, 2, \ldots$.
+    /// The $i$-th $R$ is preceded by exactly $f(i)$ $U$s, so [`floor_sum`] and its variants can be computed with
+    /// a monoid which adds the number of the $U$s at every $R$.
+    ///
+    /// # Constraints
+    ///
+    /// - $0 \leq n$
+    /// - $1 \leq m$
+    /// - $0 \leq a, b$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2(n + m + a + b))$ calls of `M::binary_operation`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{math, segtree::Monoid};
+    ///
+    /// // (the number of R, the number of U, the sum of the numbers of U before each R)
+    /// struct FloorSum;
+    ///
+    /// impl Monoid for FloorSum {
+    ///     type S = (i64, i64, i64);
+    ///
+    ///     fn identity() -> Self::S {
+    ///         (0, 0, 0)
+    ///     }
+    ///
+    ///     fn binary_operation(&(r1, u1, s1): &Self::S, &(r2, u2, s2): &Self::S) -> Self::S {
+    ///         (r1 + r2, u1 + u2, s1 + s2 + u1 * r2)
+    ///     }
+    /// }
+    ///
+    /// let (_, _, sum) = math::floor_monoid_product::<FloorSum>(6, 5, 4, 3, (0, 1, 0), (1, 0, 0));
+    /// assert_eq!(math::floor_sum(6, 5, 4, 3), sum);
+    /// ```
+    ///
+    /// [`floor_sum`]: ./fn.floor_sum.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn floor_monoid_product<M: Monoid>(
+        n: i64,
+        m: i64,
+        a: i64,
+        b: i64,
+        u: M::S,
+        r: M::S,
+    ) -> M::S {
+        assert!(0 <= n && 1 <= m && 0 <= a && 0 <= b);
+        let (mut n, mut m, mut a, mut b) = (n as u128, m as u128, a as u128, b as u128);
+        let (mut x, mut y) = (r, u);
+        let (mut pre, mut suf) = (M::identity(), M::identity());
+        loop {
+            // U^q (R U^p) (R U^p) ...
+            let (p, q) = (a / m, b / m);
+            a %= m;
+            b %= m;
+            x = M::binary_operation(&x, &monoid_pow::<M>(&y, p));
+            pre = M::binary_operation(&pre, &monoid_pow::<M>(&y, q));
+            let k = (a * n + b) / m;
+            if k == 0 {
+                break;
+            }
+            // swap the roles of the axes; the last R after the k-th U are put aside
+            let t = n - (m * k - b - 1) / a - 1;
+            suf = M::binary_operation(&M::binary_operation(&y, &monoid_pow::<M>(&x, t)), &suf);
+            n = k - 1;
+            b = m - b - 1 + a;
+            swap(&mut a, &mut m);
+            swap(&mut x, &mut y);
+        }
+        M::binary_operation(&M::binary_operation(&pre, &monoid_pow::<M>(&x, n)), &suf)
+    }
+
+    /// Returns $\min_{0 \leq i < n} ((a \times i + b) \bmod m)$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq n$
+    /// - $1 \leq m$
+    /// - $0 \leq a, b$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log^2(n + m + a + b))$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// // 3, 2, 1, 0, 4, 3
+    /// assert_eq!(math::min_of_mod_linear(3, 5, 4, 3), 1);
+    /// assert_eq!(math::min_of_mod_linear(6, 5, 4, 3), 0);
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn min_of_mod_linear(n: i64, m: i64, a: i64, b: i64) -> i64 {
+        assert!(1 <= n && 1 <= m && 0 <= a && 0 <= b);
+        let (a, b) = (a % m, b % m);
+        // (a * i + b) mod m = b + (a * i - m * f(i)), where f(i) is the number of U before the i-th R
+        let u = (-i128::from(m), None);
+        let r = (i128::from(a), Some(0));
+        let (_, min) = floor_monoid_product::<MinOfModLinear>(n, m, a, b, u, r);
+        (i128::from(b) + min.unwrap()) as i64
+    }
+
+    /// Returns whether $n$ is a prime.
+    ///
+    /// This is the deterministic Miller–Rabin test with the seven bases $2, 325, 9375, 28178, 450775, 9780504$
+    /// and $1795265022$, which are known to be correct for all $n < 2^{64}$.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(\log n)$ multiplications of `u128`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert!(math::is_prime_u64(998_244_353));
+    /// assert!(math::is_prime_u64((1 << 61) - 1));
+    /// assert!(!math::is_prime_u64(3_215_031_751)); // 151 * 751 * 28351
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn is_prime_u64(n: u64) -> bool {
+        if n < 64 {
+            return (1u64 << n) & 0x2820_8a20_a08a_28ac != 0;
+        }
+        if n % 2 == 0 {
+            return false;
+        }
+        let mul = |a: u64, b: u64| (u128::from(a) * u128::from(b) % u128::from(n)) as u64;
+        let s = (n - 1).trailing_zeros();
+        let d = (n - 1) >> s;
+        for &a in &[2, 325, 9375, 28178, 450_775, 9_780_504, 1_795_265_022] {
+            let a = a % n;
+            if a == 0 {
+                continue;
+            }
+            let mut x = 1;
+            let (mut y, mut e) = (a, d);
+            while e > 0 {
+                if e & 1 == 1 {
+                    x = mul(x, y);
+                }
+                y = mul(y, y);
+                e >>= 1;
+            }
+            if x == 1 || x == n - 1 {
+                continue;
+            }
+            let mut composite = true;
+            for _ in 1..s {
+                x = mul(x, x);
+                if x == n - 1 {
+                    composite = false;
+                    break;
+                }
+            }
+            if composite {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the prime factorization of $n$ as the pairs of a prime and its exponent in the ascending order of the
+    /// primes.
+    ///
+    /// The factors are found with Pollard's rho algorithm and [`is_prime_u64`].
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^{1/4})$ multiplications of `u128` on average
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert_eq!(vec![(2, 2), (3, 1), (5, 1)], math::factorize(60));
+    /// assert_eq!(
+    ///     vec![(4_294_967_279, 1), (4_294_967_291, 1)],
+    ///     math::factorize(4_294_967_279 * 4_294_967_291),
+    /// );
+    /// assert!(math::factorize(1).is_empty());
+    /// ```
+    ///
+    /// [`is_prime_u64`]: ./fn.is_prime_u64.html
+    pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+        assert!(1 <= n);
+        let mut primes = vec![];
+        let twos = n.trailing_zeros();
+        n >>= twos;
+        let mut stack = vec![n];
+        while let Some(n) = stack.pop() {
+            if n == 1 {
+                continue;
+            }
+            if is_prime_u64(n) {
+                primes.push(n);
+                continue;
+            }
+            let d = pollard_rho(n);
+            stack.push(d);
+            stack.push(n / d);
+        }
+        primes.sort();
+
+        let mut factors = if twos > 0 { vec![(2, twos)] } else { vec![] };
+        for p in primes {
+            match factors.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => factors.push((p, 1)),
+            }
+        }
+        factors
+    }
+
+    /// Returns the divisors of $n$ in the ascending order.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^{1/4} + d(n) \log d(n))$ where $d(n)$ is the number of the divisors
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert_eq!(vec![1, 2, 3, 4, 6, 12], math::divisors(12));
+    /// ```
+    pub fn divisors(n: u64) -> Vec<u64> {
+        let mut divisors = vec![1];
+        for (p, e) in factorize(n) {
+            let len = divisors.len();
+            let mut pk = 1;
+            for _ in 0..e {
+                pk *= p;
+                for i in 0..len {
+                    divisors.push(divisors[i] * pk);
+                }
+            }
+        }
+        divisors.sort();
+        divisors
+    }
+
+    /// Returns Euler's totient function $\varphi(n)$, the number of $1 \leq k \leq n$ coprime to $n$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^{1/4})$ on average
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert_eq!(4, math::euler_phi(12));
+    /// ```
+    pub fn euler_phi(n: u64) -> u64 {
+        factorize(n)
+            .into_iter()
+            .fold(n, |phi, (p, _)| phi / p * (p - 1))
+    }
+
+    /// Returns Carmichael's function $\lambda(n)$, the smallest $m \geq 1$ such that $a^m \equiv 1 \pmod n$ for
+    /// all $a$ coprime to $n$.
+    ///
+    /// # Constraints
+    ///
+    /// - $1 \leq n$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^{1/4})$ on average
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert_eq!(2, math::carmichael_lambda(12));
+    /// assert_eq!(80, math::carmichael_lambda(561));
+    /// ```
+    pub fn carmichael_lambda(n: u64) -> u64 {
+        factorize(n).into_iter().fold(1, |lambda, (p, e)| {
+            let l = if p == 2 && e >= 3 {
+                1 << (e - 2)
+            } else {
+                p.pow(e - 1) * (p - 1)
+            };
+            lambda / gcd(lambda, l) * l
+        })
+    }
+
+    /// A table of the smallest prime factors of $0, 1, \ldots, n$ built by the linear sieve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math::Sieve;
+    ///
+    /// let sieve = Sieve::new(100_000);
+    /// assert_eq!(&[2, 3, 5, 7, 11], &sieve.primes()[..5]);
+    /// assert_eq!(vec![(2, 2), (3, 1), (5, 1)], sieve.factorize(60));
+    /// assert_eq!(
+    ///     vec![1_000_000_007, 1_000_000_009],
+    ///     sieve.primes_in_range(1_000_000_000, 1_000_000_010),
+    /// );
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct Sieve {
+        // spf[x] = 0 for x < 2
+        spf: Vec<u32>,
+        primes: Vec<usize>,
+    }
+
+    impl Sieve {
+        /// Builds the table for $0, 1, \ldots, n$.
+        ///
+        /// # Constraints
+        ///
+        /// - $n < 2^{32}$
+        ///
+        /// # Panics
+        ///
+        /// Panics if the above constraints are not satisfied.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn new(n: usize) -> Self {
+            assert!(n as u64 <= u64::from(std::u32::MAX));
+            let mut spf = vec![0; n + 1];
+            let mut primes = vec![];
+            for i in 2..=n {
+                if spf[i] == 0 {
+                    spf[i] = i as u32;
+                    primes.push(i);
+                }
+                let spf_i = spf[i] as usize;
+                for &p in &primes {
+                    if p > spf_i || i * p > n {
+                        break;
+                    }
+                    spf[i * p] = p as u32;
+                }
+            }
+            Self { spf, primes }
+        }
+
+        /// Returns $n$.
+        pub fn limit(&self) -> usize {
+            self.spf.len() - 1
+        }
+
+        /// Returns the primes not greater than $n$ in the ascending order.
+        pub fn primes(&self) -> &[usize] {
+            &self.primes
+        }
+
+        /// Returns whether $x$ is a prime.
+        ///
+        /// # Constraints
+        ///
+        /// - $x \leq n$
+        ///
+        /// # Panics
+        ///
+        /// Panics if the above constraints are not satisfied.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(1)$
+        pub fn is_prime(&self, x: usize) -> bool {
+            assert!(x <= self.limit());
+            x >= 2 && self.spf[x] as usize == x
+        }
+
+        /// Returns the smallest prime factor of $x$.
+        ///
+        /// # Constraints
+        ///
+        /// - $2 \leq x \leq n$
+        ///
+        /// # Panics
+        ///
+        /// Panics if the above constraints are not satisfied.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(1)$
+        pub fn smallest_prime_factor(&self, x: usize) -> usize {
+            assert!(2 <= x && x <= self.limit());
+            self.spf[x] as usize
+        }
+
+        /// Returns the prime factorization of $x$ in the same form as [`factorize`].
+        ///
+        /// # Constraints
+        ///
+        /// - $1 \leq x \leq n$
+        ///
+        /// # Panics
+        ///
+        /// Panics if the above constraints are not satisfied.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(\log x)$
+        ///
+        /// [`factorize`]: ./fn.factorize.html
+        pub fn factorize(&self, mut x: usize) -> Vec<(usize, u32)> {
+            assert!(1 <= x && x <= self.limit());
+            let mut factors = vec![];
+            while x > 1 {
+                let p = self.spf[x] as usize;
+                let mut e = 0;
+                while x % p == 0 {
+                    x /= p;
+                    e += 1;
+                }
+                factors.push((p, e));
+            }
+            factors
+        }
+
+        /// Returns whether each of $l, l + 1, \ldots, r - 1$ is a prime, using the primes of the table as the sieve.
+        ///
+        /// # Constraints
+        ///
+        /// - $l \leq r \leq (n + 1)^2$
+        ///
+        /// # Panics
+        ///
+        /// Panics if the above constraints are not satisfied.
+        ///
+        /// # Complexity
+        ///
+        /// - $O((r - l) \log \log r + \sqrt{r})$
+        pub fn segmented_sieve(&self, l: u64, r: u64) -> Vec<bool> {
+            assert!(l <= r);
+            let limit = self.limit() as u64 + 1;
+            assert!(r <= limit.saturating_mul(limit));
+            let mut is_prime = vec![true; (r - l) as usize];
+            for x in l..r.min(2) {
+                is_prime[(x - l) as usize] = false;
+            }
+            for &p in &self.primes {
+                let p = p as u64;
+                if p * p >= r {
+                    break;
+                }
+                let start = (p * p).max(l + (p - l % p) % p);
+                let mut x = start;
+                while x < r {
+                    is_prime[(x - l) as usize] = false;
+                    x += p;
+                }
+            }
+            is_prime
+        }
+
+        /// Returns the primes in $[l, r)$ in the ascending order.
+        ///
+        /// # Constraints
+        ///
+        /// - $l \leq r \leq (n + 1)^2$
+        ///
+        /// # Panics
+        ///
+        /// Panics if the above constraints are not satisfied.
+        ///
+        /// # Complexity
+        ///
+        /// - $O((r - l) \log \log r + \sqrt{r})$
+        pub fn primes_in_range(&self, l: u64, r: u64) -> Vec<u64> {
+            self.segmented_sieve(l, r)
+                .into_iter()
+                .zip(l..)
+                .filter(|&(is_prime, _)| is_prime)
+                .map(|(_, x)| x)
+                .collect()
+        }
+
+        /// Returns the Möbius function $\mu(0), \mu(1), \ldots, \mu(n)$, where $\mu(0) = 0$.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn mobius_table(&self) -> Vec<i32> {
+            let mut mu = vec![0; self.spf.len()];
+            if mu.len() > 1 {
+                mu[1] = 1;
+            }
+            for x in 2..mu.len() {
+                let p = self.spf[x] as usize;
+                let y = x / p;
+                mu[x] = if y % p == 0 { 0 } else { -mu[y] };
+            }
+            mu
+        }
+
+        /// Returns Euler's totient function $\varphi(0), \varphi(1), \ldots, \varphi(n)$, where $\varphi(0) = 0$.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn euler_phi_table(&self) -> Vec<usize> {
+            let mut phi = vec![0; self.spf.len()];
+            if phi.len() > 1 {
+                phi[1] = 1;
+            }
+            for x in 2..phi.len() {
+                let p = self.spf[x] as usize;
+                let y = x / p;
+                phi[x] = if y % p == 0 {
+                    phi[y] * p
+                } else {
+                    phi[y] * (p - 1)
+                };
+            }
+            phi
+        }
+
+        /// Returns the numbers of the divisors $d(0), d(1), \ldots, d(n)$, where $d(0) = 0$.
+        ///
+        /// # Complexity
+        ///
+        /// - $O(n)$
+        pub fn divisor_count_table(&self) -> Vec<usize> {
+            let mut d = vec![0; self.spf.len()];
+            // the exponent of the smallest prime factor
+            let mut e = vec![0; self.spf.len()];
+            if d.len() > 1 {
+                d[1] = 1;
+            }
+            for x in 2..d.len() {
+                let p = self.spf[x] as usize;
+                let y = x / p;
+                if y % p == 0 {
+                    e[x] = e[y] + 1;
+                    d[x] = d[y] / (e[y] + 1) * (e[x] + 1);
+                } else {
+                    e[x] = 1;
+                    d[x] = d[y] * 2;
+                }
+            }
+            d
+        }
+    }
+
+    /// Returns $\pi(n)$, the number of the primes not greater than $n$.
+    ///
+    /// This is Lucy's algorithm, which is the dynamic programming at the core of the Meissel–Lehmer method.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^{3/4} / \log n)$
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// assert_eq!(4, math::prime_pi(10));
+    /// assert_eq!(5_761_455, math::prime_pi(100_000_000));
+    /// ```
+    pub fn prime_pi(n: u64) -> u64 {
+        if n < 2 {
+            return 0;
+        }
+        let r = isqrt(n) as usize;
+        let sieve = Sieve::new(r);
+        let (_, large) = lucy(n, sieve.primes(), |_| 1, |v| v as i64 - 1);
+        large[1] as u64
+    }
+
+    /// Returns $\sum_{i = 1}^n f(i)$ for a multiplicative function $f$ with the min_25 sieve.
+    ///
+    /// $f$ is given by `prime_poly` and `f`:
+    ///
+    /// - $f(p) = \sum_k \mathrm{prime\\_poly}_k\ p^k$ for every prime $p$.
+    /// - `f(p, e)` returns $f(p^e)$. It is called only with $p^e \leq n$ and must agree with `prime_poly` for $e = 1$.
+    ///
+    /// The computation is done modulo $2^{64}$, so the result is exact whenever it fits in `i64`.
+    /// To compute the sum modulo a prime, return the values of $f(p^e)$ modulo it and reduce the result.
+    ///
+    /// # Constraints
+    ///
+    /// - `prime_poly.len()` $\leq 4$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraints are not satisfied.
+    ///
+    /// # Complexity
+    ///
+    /// - $O(n^{3/4} / \log n)$ calls of `f` and arithmetic operations for each term of `prime_poly`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::math;
+    ///
+    /// // the sum of the totients: φ(p) = p - 1, φ(p^e) = p^{e - 1} (p - 1)
+    /// let sum = math::multiplicative_prefix_sum(10, &[-1, 1], |p, e| {
+    ///     (p.pow(e - 1) * (p - 1)) as i64
+    /// });
+    /// assert_eq!(32, sum);
+    ///
+    /// // the Mertens function
+    /// let mertens = math::multiplicative_prefix_sum(
+    ///     1_000_000_000,
+    ///     &[-1],
+    ///     |_, e| if e == 1 { -1 } else { 0 },
+    /// );
+    /// assert_eq!(-222, mertens);
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn multiplicative_prefix_sum<F>(n: u64, prime_poly: &[i64], mut f: F) -> i64
+    where
+        F: FnMut(u64, u32) -> i64,
+    {
+        assert!(prime_poly.len() <= 4);
+        if n == 0 {
+            return 0;
+        }
+        let r = isqrt(n) as usize;
+        let sieve = Sieve::new(r);
+        let primes = sieve.primes();
+
+        // the sums of f(p) over the primes p <= v for every v = floor(n / k)
+        let mut small = vec![0i64; r + 1];
+        let mut large = vec![0i64; r + 1];
+        for (k, &c) in prime_poly.iter().enumerate() {
+            if c == 0 {
+                continue;
             }
-            // u1 * u1 <= mi * mi / g / g <= m0 * mi / g = lcm(m0, mi)
-            let x = (ri - r0) / g % u1 * im % u1;
+            let (s, l) = lucy(
+                n,
+                primes,
+                |p| (p as i64).wrapping_pow(k as u32),
+                |v| power_sum(v, k).wrapping_sub(1) as i64,
+            );
+            for (x, y) in small.iter_mut().zip(s).chain(large.iter_mut().zip(l)) {
+                *x = x.wrapping_add(c.wrapping_mul(y));
+            }
+        }
 
-            // |r0| + |m0 * x|
-            // < m0 + m0 * (u1 - 1)
-            // = m0 + m0 * mi / g - m0
-            // = lcm(m0, mi)
-            r0 += x * m0;
-            m0 *= u1; // -> lcm(m0, mi)
-            if r0 < 0 {
-                r0 += m0
+        // the sum of f(i) over 2 <= i <= v whose prime factors are not less than primes[j]
+        #[allow(clippy::many_single_char_names, clippy::too_many_arguments)]
+        fn rec<F: FnMut(u64, u32) -> i64>(
+            n: u64,
+            v: u64,
+            j: usize,
+            primes: &[usize],
+            small: &[i64],
+            large: &[i64],
+            f: &mut F,
+        ) -> i64 {
+            let prime_sum = |v: u64| {
+                if (v as usize) < small.len() {
+                    small[v as usize]
+                } else {
+                    large[(n / v) as usize]
+                }
             };
+            let below = if j == 0 { 0 } else { small[primes[j - 1]] };
+            let mut sum = prime_sum(v).wrapping_sub(below);
+            for (i, &p) in primes.iter().enumerate().skip(j) {
+                let p = p as u64;
+                if p * p > v {
+                    break;
+                }
+                let (mut pe, mut e) = (p, 1);
+                while pe * p <= v {
+                    let rest = rec(n, v / pe, i + 1, primes, small, large, f);
+                    sum = sum
+                        .wrapping_add(f(p, e).wrapping_mul(rest))
+                        .wrapping_add(f(p, e + 1));
+                    pe *= p;
+                    e += 1;
+                }
+            }
+            sum
         }
 
-        (r0, m0)
+        rec(n, n, 0, primes, &small, &large, &mut f).wrapping_add(1)
     }
 
-    /// Returns $\sum_{i = 0}^{n - 1} \lfloor \frac{a \times i + b}{m} \rfloor$.
+    /// Returns $F(n) = \sum_{i = 1}^n f(i)$ with the Dirichlet hyperbola method, where $f$ is given through
+    /// $f * g = h$ for the Dirichlet convolution $*$ and $g(1) = 1$.
     ///
-    /// # Constraints
+    /// - `small[v]` must be $F(v)$ for $v <$ `small.len()`.
+    /// - `g_sum(v)` and `h_sum(v)` must return $\sum_{i = 1}^v g(i)$ and $\sum_{i = 1}^v h(i)$.
     ///
-    /// - $0 \leq n \leq 10^9$
-    /// - $1 \leq m \leq 10^9$
-    /// - $0 \leq a, b \leq m$
+    /// The recurrence is $F(v) = H(v) - \sum_{d = 2}^v g(d) F(\lfloor v / d \rfloor)$.
+    /// `small` with the length around $n^{2/3}$ gives the best complexity, and it is typically built from a table of [`Sieve`].
+    ///
+    /// The computation is done modulo $2^{64}$, so the result is exact whenever it fits in `i64`.
     ///
     /// # Panics
     ///
-    /// Panics if the above constraints are not satisfied and overflow or division by zero occurred.
+    /// Panics if `g_sum(1)` is not $1$.
     ///
     /// # Complexity
     ///
-    /// - $O(\log(n + m + a + b))$
+    /// - $O(n / \sqrt{s})$ calls of `g_sum` and `h_sum` where $s = \max(\sqrt n, $ `small.len()` $)$
     ///
     /// # Example
     ///
     /// ```
-    /// use ac_library_rs::math;
+    /// use ac_library_rs::math::{self, Sieve};
     ///
-    /// assert_eq!(math::floor_sum(6, 5, 4, 3), 13);
+    /// // φ * 1 = id
+    /// let n = 1_000_000_000;
+    /// let phi = Sieve::new(1_000_000).euler_phi_table();
+    /// let small = phi
+    ///     .iter()
+    ///     .scan(0, |sum, &x| {
+    ///         *sum += x as i64;
+    ///         Some(*sum)
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// let sum = math::dirichlet_prefix_sum(n, &small, |v| v as i64, |v| {
+    ///     let v = v as i64;
+    ///     v * (v + 1) / 2
+    /// });
+    /// assert_eq!(303_963_551_173_008_414, sum);
     /// ```
-    pub fn floor_sum(n: i64, m: i64, mut a: i64, mut b: i64) -> i64 {
-        let mut ans = 0;
-        if a >= m {
-            ans += (n - 1) * n * (a / m) / 2;
-            a %= m;
+    ///
+    /// [`Sieve`]: ./struct.Sieve.html
+    #[allow(clippy::many_single_char_names)]
+    pub fn dirichlet_prefix_sum<G, H>(n: u64, small: &[i64], mut g_sum: G, mut h_sum: H) -> i64
+    where
+        G: FnMut(u64) -> i64,
+        H: FnMut(u64) -> i64,
+    {
+        assert_eq!(1, g_sum(1), "g(1) must be 1");
+        if n == 0 {
+            return 0;
         }
-        if b >= m {
-            ans += n * (b / m);
-            b %= m;
+        if n < small.len() as u64 {
+            return small[n as usize];
+        }
+        let r = isqrt(n);
+        // lo[v] = F(v) for v <= r, hi[k] = F(n / k) for k <= r
+        let mut lo = vec![0i64; r as usize + 1];
+        let mut hi = vec![0i64; r as usize + 1];
+        let values = (1..=r).chain((1..=r).rev().map(|k| n / k).filter(|&v| v > r));
+        for v in values {
+            let fv = if v < small.len() as u64 {
+                small[v as usize]
+            } else {
+                let mut fv = h_sum(v);
+                let mut l = 2;
+                while l <= v {
+                    let q = v / l;
+                    let r = v / q;
+                    let fq = if q < small.len() as u64 {
+                        small[q as usize]
+                    } else if q < lo.len() as u64 {
+                        lo[q as usize]
+                    } else {
+                        hi[(n / q) as usize]
+                    };
+                    let g = g_sum(r).wrapping_sub(g_sum(l - 1));
+                    fv = fv.wrapping_sub(g.wrapping_mul(fq));
+                    l = r + 1;
+                }
+                fv
+            };
+            if v <= r {
+                lo[v as usize] = fv;
+            } else {
+                hi[(n / v) as usize] = fv;
+            }
+        }
+        if n <= r {
+            lo[n as usize]
+        } else {
+            hi[1]
         }
+    }
 
-        let y_max = (a * n + b) / m;
-        let x_max = y_max * m - b;
-        if y_max == 0 {
-            return ans;
+    /// Returns a non-trivial divisor of an odd composite number $n$ with Brent's variant of Pollard's rho algorithm.
+    #[allow(clippy::many_single_char_names)]
+    fn pollard_rho(n: u64) -> u64 {
+        const BATCH: u64 = 128;
+
+        let mul = |a: u64, b: u64| (u128::from(a) * u128::from(b) % u128::from(n)) as u64;
+        let mut c = 0u64;
+        loop {
+            c += 1;
+            let f =
+                |x: u64| ((u128::from(x) * u128::from(x) + u128::from(c)) % u128::from(n)) as u64;
+            let (mut x, mut y, mut ys) = (0, 2, 2);
+            let (mut g, mut q, mut r) = (1, 1, 1);
+            while g == 1 {
+                x = y;
+                for _ in 0..r {
+                    y = f(y);
+                }
+                let mut k = 0;
+                while k < r && g == 1 {
+                    ys = y;
+                    for _ in 0..BATCH.min(r - k) {
+                        y = f(y);
+                        q = mul(q, if x > y { x - y } else { y - x });
+                    }
+                    g = gcd(q, n);
+                    k += BATCH;
+                }
+                r <<= 1;
+            }
+            if g == n {
+                // the batch overshot, so retry it one by one
+                loop {
+                    ys = f(ys);
+                    g = gcd(if x > ys { x - ys } else { ys - x }, n);
+                    if g > 1 {
+                        break;
+                    }
+                }
+            }
+            if g != n {
+                return g;
+            }
         }
-        ans += (n - (x_max + a - 1) / a) * y_max;
-        ans += floor_sum(y_max, a, m, (a - x_max % a) % a);
-        ans
+    }
+
+    /// Returns the sums of $f(i), i f(i), f(i)^2$ over $0 \leq i \leq n$ where $f(i) = \lfloor \frac{a i + b}{m} \rfloor$.
+    #[allow(clippy::many_single_char_names)]
+    fn floor_sums(n: i128, m: i128, a: i128, b: i128) -> (i128, i128, i128) {
+        let s1 = n * (n + 1) / 2;
+        let s2 = n * (n + 1) * (2 * n + 1) / 6;
+        if a >= m || b >= m {
+            let (p, q) = (a / m, b / m);
+            let (f, g, h) = floor_sums(n, m, a % m, b % m);
+            return (
+                f + p * s1 + q * (n + 1),
+                g + p * s2 + q * s1,
+                h + 2 * q * f + 2 * p * g + p * p * s2 + 2 * p * q * s1 + q * q * (n + 1),
+            );
+        }
+        let k = (a * n + b) / m;
+        if k == 0 {
+            return (0, 0, 0);
+        }
+        // count the lattice points under the line from the other axis
+        let (f2, g2, h2) = floor_sums(k - 1, a, m, m - b - 1);
+        let f = n * k - f2;
+        let g = (k * n * (n + 1) - h2 - f2) / 2;
+        let h = n * k * (k + 1) - 2 * g2 - 2 * f2 - f;
+        (f, g, h)
+    }
+
+    fn monoid_pow<M: Monoid>(x: &M::S, mut n: u128) -> M::S {
+        let mut res = M::identity();
+        let mut x = x.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                res = M::binary_operation(&res, &x);
+            }
+            x = M::binary_operation(&x, &x);
+            n >>= 1;
+        }
+        res
+    }
+
+    /// (the difference of $a x - m y$, the minimum of $a x - m y$ at each $R$ relative to the start)
+    enum MinOfModLinear {}
+
+    impl Monoid for MinOfModLinear {
+        type S = (i128, Option<i128>);
+
+        fn identity() -> Self::S {
+            (0, None)
+        }
+
+        fn binary_operation(&(d1, min1): &Self::S, &(d2, min2): &Self::S) -> Self::S {
+            let min = match (min1, min2.map(|min2| d1 + min2)) {
+                (Some(x), Some(y)) => Some(x.min(y)),
+                (x, y) => x.or(y),
+            };
+            (d1 + d2, min)
+        }
+    }
+
+    /// Returns $\lfloor \sqrt n \rfloor$.
+    fn isqrt(n: u64) -> u64 {
+        let mut r = (n as f64).sqrt() as u64;
+        while r.checked_mul(r).map_or(true, |r2| r2 > n) {
+            r -= 1;
+        }
+        while (r + 1).checked_mul(r + 1).map_or(false, |r2| r2 <= n) {
+            r += 1;
+        }
+        r
+    }
+
+    /// Returns $\sum_{i = 1}^v i^k \bmod 2^{64}$.
+    #[allow(clippy::many_single_char_names)]
+    fn power_sum(v: u64, k: usize) -> u64 {
+        let (mut a, mut b) = (v, v + 1);
+        if a % 2 == 0 {
+            a /= 2;
+        } else {
+            b /= 2;
+        }
+        match k {
+            0 => v,
+            1 => a.wrapping_mul(b),
+            2 => {
+                let mut c = 2 * v + 1;
+                if a % 3 == 0 {
+                    a /= 3;
+                } else if b % 3 == 0 {
+                    b /= 3;
+                } else {
+                    c /= 3;
+                }
+                a.wrapping_mul(b).wrapping_mul(c)
+            }
+            3 => a.wrapping_mul(b).wrapping_mul(a.wrapping_mul(b)),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the pair of the tables `small[v]` for $v \leq \sqrt n$ and `large[k]` for $k \leq \sqrt n$ of
+    /// $\sum_{p \leq v} g(p)$ over the primes, where $v = \lfloor n / k \rfloor$ for the latter.
+    ///
+    /// $g$ must be completely multiplicative, `g_sum(v)` must return $\sum_{i = 2}^v g(i)$ and `primes` must be the
+    /// primes not greater than $\sqrt n$.
+    #[allow(clippy::many_single_char_names)]
+    fn lucy(
+        n: u64,
+        primes: &[usize],
+        g: impl Fn(u64) -> i64,
+        g_sum: impl Fn(u64) -> i64,
+    ) -> (Vec<i64>, Vec<i64>) {
+        let r = isqrt(n) as usize;
+        let mut small = (0..=r as u64)
+            .map(|v| if v == 0 { 0 } else { g_sum(v) })
+            .collect::<Vec<_>>();
+        let mut large = (0..=r as u64)
+            .map(|k| if k == 0 { 0 } else { g_sum(n / k) })
+            .collect::<Vec<_>>();
+        for &p in primes {
+            let below = small[p - 1];
+            let gp = g(p as u64);
+            let p2 = (p * p) as u64;
+            for k in 1..=r.min((n / p2) as usize) {
+                let d = k * p;
+                let s = if d <= r {
+                    large[d]
+                } else {
+                    small[(n / d as u64) as usize]
+                };
+                large[k] = large[k].wrapping_sub(gp.wrapping_mul(s.wrapping_sub(below)));
+            }
+            for v in (p * p..=r).rev() {
+                small[v] = small[v].wrapping_sub(gp.wrapping_mul(small[v / p].wrapping_sub(below)));
+            }
+        }
+        (small, large)
+    }
+
+    fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+        while b != 0 {
+            a %= b;
+            swap(&mut a, &mut b);
+        }
+        a
+    }
+
+    /// Returns $a b \bmod m$ without overflow.
+    fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+        let add = |x: u128, y: u128| if x >= m - y { x - (m - y) } else { x + y };
+        let (mut a, mut b) = (a % m, b % m);
+        let mut res = 0;
+        while b > 0 {
+            if b & 1 == 1 {
+                res = add(res, a);
+            }
+            a = add(a, a);
+            b >>= 1;
+        }
+        res
+    }
+
+    /// Returns $a^{-1} \bmod m$ for $\gcd(a, m) = 1$.
+    #[allow(clippy::many_single_char_names)]
+    fn inv_mod_u128(a: u128, m: u128) -> u128 {
+        // s = m0 * a, t = m1 * a (mod m)
+        let (mut s, mut t) = (m, a % m);
+        let (mut m0, mut m1) = (0, 1 % m);
+        while t != 0 {
+            let u = s / t;
+            s -= t * u;
+            let sub = mul_mod_u128(m1, u, m);
+            m0 = if m0 >= sub { m0 - sub } else { m - (sub - m0) };
+            swap(&mut s, &mut t);
+            swap(&mut m0, &mut m1);
+        }
+        debug_assert_eq!(1, s);
+        m0
+    }
+
+    fn gcd(mut a: u64, mut b: u64) -> u64 {
+        while b != 0 {
+            a %= b;
+            swap(&mut a, &mut b);
+        }
+        a
     }
 
     #[cfg(test)]
@@ -305,6 +1677,123 @@ mod math {
             assert_eq!(crt(&a, &b), (39, 45));
         }
 
+        #[test]
+        fn test_crt_checked() {
+            for m0 in 1..=20 {
+                for m1 in 1..=20 {
+                    for r0 in -20..20 {
+                        for r1 in -20..20 {
+                            let (r, m) = ([r0, r1], [m0, m1]);
+                            let lcm = m0 * m1 / internal_math::inv_gcd(m0, m1).0;
+                            let expected = (0..lcm).find(|&x| {
+                                (x - r0).rem_euclid(m0) == 0 && (x - r1).rem_euclid(m1) == 0
+                            });
+                            match expected {
+                                Some(x) => {
+                                    assert_eq!(Ok((x, lcm)), crt_checked(&r, &m));
+                                    let r = [r0.rem_euclid(m0) as u128, r1.rem_euclid(m1) as u128];
+                                    let m = [m0 as u128, m1 as u128];
+                                    assert_eq!(Ok((x as u128, lcm as u128)), crt_u128(&r, &m));
+                                }
+                                None => {
+                                    let err = crt_checked(&r, &m).unwrap_err();
+                                    assert_eq!(&CrtErrorKind::NoSolution, err.kind());
+                                    assert_eq!((0, 0), crt(&r, &m));
+                                    let r = [r0.rem_euclid(m0) as u128, r1.rem_euclid(m1) as u128];
+                                    let m = [m0 as u128, m1 as u128];
+                                    let err = crt_u128(&r, &m).unwrap_err();
+                                    assert_eq!(&CrtErrorKind::NoSolution, err.kind());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            let m = [1_000_000_007, 998_244_353, 1_000_000_009];
+            let err = crt_checked(&[1, 2, 3], &m).unwrap_err();
+            assert_eq!(&CrtErrorKind::Overflow, err.kind());
+            // the answer fits while the product of the moduli does not
+            let m = [3_037_000_499 * 2, 3_037_000_499 * 3];
+            assert_eq!(Ok((5, 3_037_000_499 * 6)), crt_checked(&[5, 5], &m));
+            let m = [i64::max_value(), i64::max_value() - 1];
+            let err = crt_checked(&[1, 1], &m).unwrap_err();
+            assert_eq!(&CrtErrorKind::Overflow, err.kind());
+        }
+
+        #[test]
+        fn test_crt_u128() {
+            let p = [
+                18446744073709551557u128,
+                2305843009213693951,
+                1_000_000_007,
+                998_244_353,
+            ];
+            let x = 0x1234_5678_9abc_def0_1234_5678_9abc_def0u128;
+            let r = p.iter().map(|&p| x % p).collect::<Vec<_>>();
+            let (y, z) = crt_u128(&r[..2], &p[..2]).unwrap();
+            assert_eq!((x % (p[0] * p[1]), p[0] * p[1]), (y, z));
+            let err = crt_u128(&r, &p).unwrap_err();
+            assert_eq!(&CrtErrorKind::Overflow, err.kind());
+
+            // non-coprime large moduli
+            let g = 1u128 << 70;
+            let m = [g * 3, g * 5, g * 7];
+            let x = (g * 105) - 12345;
+            let r = m.iter().map(|&m| x % m).collect::<Vec<_>>();
+            assert_eq!(Ok((x, g * 105)), crt_u128(&r, &m));
+            let err = crt_u128(&[0, 1], &[g, g * 2]).unwrap_err();
+            assert_eq!(&CrtErrorKind::NoSolution, err.kind());
+            assert_eq!(Ok((0, 1)), crt_u128(&[], &[]));
+            assert_eq!(
+                Ok((u128::max_value() - 1, u128::max_value())),
+                crt_u128(&[u128::max_value() - 1], &[u128::max_value()]),
+            );
+        }
+
+        #[test]
+        fn test_garner() {
+            let m = [3, 5, 7, 11, 13];
+            for x in 0..3 * 5 * 7 * 11 * 13 {
+                let r = m.iter().map(|&m| x % m).collect::<Vec<_>>();
+                for &t in &[1, 2, 100, 998_244_353, i64::max_value()] {
+                    assert_eq!(x % t, garner(&r, &m, t));
+                }
+            }
+            assert_eq!(0, garner(&[], &[], 10));
+            assert_eq!(3, garner(&[100, 3], &[1, 10], 10));
+            assert_eq!(
+                crt(&[1, i64::min_value()], &[3, 5]).0 % 7,
+                garner(&[1, i64::min_value()], &[3, 5], 7)
+            );
+            assert_eq!(
+                crt(&[i64::max_value(), i64::min_value()], &[2, 3]).0,
+                garner(&[i64::max_value(), i64::min_value()], &[2, 3], 100),
+            );
+
+            let m = [
+                1_000_000_007,
+                998_244_353,
+                1_000_000_009,
+                i64::max_value(),
+                4_611_686_018_427_387_847,
+            ];
+            let x = 0x0123_4567_89ab_cdef_0123_4567_89ab_cdefu128;
+            let r = m
+                .iter()
+                .map(|&m| (x % m as u128) as i64)
+                .collect::<Vec<_>>();
+            for &t in &[998_244_353, i64::max_value() - 1] {
+                assert_eq!((x % t as u128) as i64, garner(&r, &m, t));
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_garner_not_coprime() {
+            garner(&[1, 1], &[4, 6], 100);
+        }
+
         #[test]
         fn test_floor_sum() {
             assert_eq!(floor_sum(0, 1, 0, 0), 0);
@@ -315,5 +1804,445 @@ mod math {
             );
             assert_eq!(floor_sum(332955, 5590132, 2231, 999423), 22014575);
         }
+
+        #[test]
+        fn test_floor_sum_weighted_squared() {
+            for n in 0..20 {
+                for m in 1..20 {
+                    for a in 0..=m {
+                        for b in 0..=m {
+                            let f = |i: i64| ((a * i + b) / m) as i128;
+                            let weighted = (0..n).map(|i| i as i128 * f(i)).sum::<i128>();
+                            let squared = (0..n).map(|i| f(i) * f(i)).sum::<i128>();
+                            assert_eq!(weighted, floor_sum_weighted(n, m, a, b));
+                            assert_eq!(squared, floor_sum_squared(n, m, a, b));
+                        }
+                    }
+                }
+            }
+
+            let n = 1_000_000_000i128;
+            // both are the sum of i^2 over i < n
+            let s2 = (n - 1) * n * (2 * n - 1) / 6;
+            assert_eq!(s2, floor_sum_weighted(1_000_000_000, 1, 1, 0));
+            assert_eq!(s2, floor_sum_squared(1_000_000_000, 1, 1, 0));
+            let (n, m, a, b) = (1_000_000, 999_999_937, 998_244_353, 1_000_000_007);
+            let f = |i: i64| ((a * i + b) / m) as i128;
+            let weighted = (0..n).map(|i| i as i128 * f(i)).sum::<i128>();
+            let squared = (0..n).map(|i| f(i) * f(i)).sum::<i128>();
+            assert_eq!(weighted, floor_sum_weighted(n, m, a, b));
+            assert_eq!(squared, floor_sum_squared(n, m, a, b));
+        }
+
+        #[test]
+        fn test_floor_monoid_product() {
+            enum Concat {}
+
+            impl Monoid for Concat {
+                type S = String;
+
+                fn identity() -> String {
+                    String::new()
+                }
+
+                fn binary_operation(a: &String, b: &String) -> String {
+                    a.clone() + b
+                }
+            }
+
+            for n in 0..10 {
+                for m in 1..10 {
+                    for a in 0..15 {
+                        for b in 0..15 {
+                            let f = |i: i64| (a * i + b) / m;
+                            let mut expected = "U".repeat(f(0) as usize);
+                            for i in 1..=n {
+                                expected += "R";
+                                expected += &"U".repeat((f(i) - f(i - 1)) as usize);
+                            }
+                            let actual = floor_monoid_product::<Concat>(
+                                n,
+                                m,
+                                a,
+                                b,
+                                "U".to_owned(),
+                                "R".to_owned(),
+                            );
+                            assert_eq!(expected, actual, "{} {} {} {}", n, m, a, b);
+                        }
+                    }
+                }
+            }
+
+            // (R, U, the sum of U before each R)
+            enum FloorSum {}
+
+            impl Monoid for FloorSum {
+                type S = (i64, i64, i64);
+
+                fn identity() -> Self::S {
+                    (0, 0, 0)
+                }
+
+                fn binary_operation(&(r1, u1, s1): &Self::S, &(r2, u2, s2): &Self::S) -> Self::S {
+                    (r1 + r2, u1 + u2, s1 + s2 + u1 * r2)
+                }
+            }
+
+            for &(n, m, a, b) in &[
+                (1_000_000_000, 1, 1, 1),
+                (1_000_000_000, 1_000_000_000, 999_999_999, 999_999_999),
+                (332955, 5590132, 2231, 999423),
+            ] {
+                let (r, u, s) = floor_monoid_product::<FloorSum>(n, m, a, b, (0, 1, 0), (1, 0, 0));
+                assert_eq!(n, r);
+                assert_eq!((a * n + b) / m, u);
+                assert_eq!(floor_sum(n, m, a, b), s);
+            }
+        }
+
+        #[test]
+        fn test_min_of_mod_linear() {
+            for n in 1..20 {
+                for m in 1..20 {
+                    for a in 0..25 {
+                        for b in 0..25 {
+                            let expected = (0..n).map(|i| (a * i + b) % m).min().unwrap();
+                            assert_eq!(expected, min_of_mod_linear(n, m, a, b));
+                        }
+                    }
+                }
+            }
+
+            let m = 998_244_353;
+            // 3 i + 1 = 2 m first at i = 665496235
+            assert_eq!(1, min_of_mod_linear(665_496_235, m, 3, 1));
+            assert_eq!(0, min_of_mod_linear(665_496_236, m, 3, 1));
+            let x = 1_000_000_000_000_000_000;
+            let m = 1_000_000_000_000_000_003;
+            assert_eq!(
+                (0..1000)
+                    .map(|i| (x as i128 * i + 5) % m as i128)
+                    .min()
+                    .unwrap() as i64,
+                min_of_mod_linear(1000, m, x, 5),
+            );
+            // x i + 1 = 0 for i = -x^{-1} mod m
+            assert_eq!(0, min_of_mod_linear(m - 1, m, x, 1));
+        }
+
+        #[test]
+        fn test_is_prime_u64() {
+            let n = 100_000;
+            let mut sieve = vec![true; n];
+            sieve[0] = false;
+            sieve[1] = false;
+            for i in 2..n {
+                if sieve[i] {
+                    for j in (2 * i..n).step_by(i) {
+                        sieve[j] = false;
+                    }
+                }
+            }
+            for (i, &is_prime) in sieve.iter().enumerate() {
+                assert_eq!(is_prime, is_prime_u64(i as u64), "{}", i);
+            }
+
+            for &p in &[
+                998244353,
+                1000000007,
+                2305843009213693951,
+                18446744073709551557,
+                4294967291,
+                4294967311,
+            ] {
+                assert!(is_prime_u64(p), "{}", p);
+            }
+            for &n in &[
+                // strong pseudoprimes to some of the small bases
+                2047,
+                3215031751,
+                3825123056546413051,
+                // Carmichael numbers
+                561,
+                41041,
+                // squares of primes
+                4294967291 * 4294967291,
+                998244353 * 998244353,
+                4294967291 * 4294967279,
+                u64::max_value(),
+            ] {
+                assert!(!is_prime_u64(n), "{}", n);
+            }
+        }
+
+        #[test]
+        fn test_factorize() {
+            for n in 1..10000 {
+                let mut m = n;
+                let mut expected = vec![];
+                for p in 2..=n {
+                    let mut e = 0;
+                    while m % p == 0 {
+                        m /= p;
+                        e += 1;
+                    }
+                    if e > 0 {
+                        expected.push((p, e));
+                    }
+                }
+                assert_eq!(expected, factorize(n));
+            }
+
+            assert_eq!(
+                vec![
+                    (3, 1),
+                    (5, 1),
+                    (17, 1),
+                    (257, 1),
+                    (641, 1),
+                    (65537, 1),
+                    (6700417, 1)
+                ],
+                factorize(u64::max_value()),
+            );
+            assert_eq!(vec![(2, 63)], factorize(1 << 63));
+            assert_eq!(vec![(4294967291, 2)], factorize(4294967291 * 4294967291));
+            assert_eq!(vec![(3, 40)], factorize(3u64.pow(40)));
+            assert_eq!(
+                vec![(999999937, 1), (1000000007, 1)],
+                factorize(999999937 * 1000000007),
+            );
+            assert_eq!(
+                vec![(2, 3), (998244353, 1), (1000000007, 1)],
+                factorize(8 * 998244353 * 1000000007),
+            );
+        }
+
+        #[test]
+        fn test_sieve() {
+            for n in 0..100 {
+                let sieve = Sieve::new(n);
+                assert_eq!(n, sieve.limit());
+                let primes = (2..=n)
+                    .filter(|&x| (2..x).all(|d| x % d != 0))
+                    .collect::<Vec<_>>();
+                assert_eq!(&primes[..], sieve.primes());
+                for x in 0..=n {
+                    assert_eq!(primes.contains(&x), sieve.is_prime(x));
+                }
+            }
+
+            let n = 100_000;
+            let sieve = Sieve::new(n);
+            assert_eq!(9592, sieve.primes().len());
+            for x in 2..=n {
+                let spf = (2..=x).find(|&d| x % d == 0).unwrap();
+                assert_eq!(spf, sieve.smallest_prime_factor(x));
+            }
+            for x in 1..=n {
+                let expected = factorize(x as u64)
+                    .into_iter()
+                    .map(|(p, e)| (p as usize, e))
+                    .collect::<Vec<_>>();
+                assert_eq!(expected, sieve.factorize(x));
+            }
+        }
+
+        #[test]
+        fn test_sieve_segmented() {
+            let sieve = Sieve::new(1000);
+            for l in 0..100 {
+                for r in l..100 {
+                    let expected = (l..r).map(is_prime_u64).collect::<Vec<_>>();
+                    assert_eq!(expected, sieve.segmented_sieve(l, r));
+                }
+            }
+            assert_eq!(
+                (0..=1_002_001)
+                    .filter(|&x| is_prime_u64(x))
+                    .collect::<Vec<_>>(),
+                sieve.primes_in_range(0, 1_002_001),
+            );
+
+            let sieve = Sieve::new(1_000_000);
+            let (l, r) = (1_000_000_000_000, 1_000_000_100_000);
+            let expected = (l..r).filter(|&x| is_prime_u64(x)).collect::<Vec<_>>();
+            assert_eq!(expected, sieve.primes_in_range(l, r));
+            assert_eq!(
+                vec![999_999_999_989],
+                sieve.primes_in_range(999_999_999_980, l)
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_sieve_segmented_too_large() {
+            Sieve::new(1000).segmented_sieve(0, 1_002_002);
+        }
+
+        #[test]
+        fn test_sieve_tables() {
+            let n = 1000;
+            let sieve = Sieve::new(n);
+            let mu = sieve.mobius_table();
+            let phi = sieve.euler_phi_table();
+            let d = sieve.divisor_count_table();
+            assert_eq!((0, 0, 0), (mu[0], phi[0], d[0]));
+            for x in 1..=n {
+                let factors = factorize(x as u64);
+                let expected = if factors.iter().any(|&(_, e)| e > 1) {
+                    0
+                } else if factors.len() % 2 == 0 {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(expected, mu[x]);
+                assert_eq!(euler_phi(x as u64), phi[x] as u64);
+                assert_eq!(divisors(x as u64).len(), d[x]);
+            }
+            assert_eq!((vec![0], vec![0], vec![0]), {
+                let sieve = Sieve::new(0);
+                (
+                    sieve.mobius_table(),
+                    sieve.euler_phi_table(),
+                    sieve.divisor_count_table(),
+                )
+            });
+        }
+
+        #[test]
+        fn test_prime_pi() {
+            let sieve = Sieve::new(10000);
+            let mut count = 0;
+            for n in 0..=10000 {
+                if sieve.is_prime(n) {
+                    count += 1;
+                }
+                assert_eq!(count, prime_pi(n as u64));
+            }
+            assert_eq!(50847534, prime_pi(1_000_000_000));
+            assert_eq!(455052511, prime_pi(10_000_000_000));
+        }
+
+        #[test]
+        fn test_multiplicative_prefix_sum() {
+            let sieve = Sieve::new(10000);
+            let mu = sieve.mobius_table();
+            let phi = sieve.euler_phi_table();
+            let d = sieve.divisor_count_table();
+            let (mut mu_sum, mut phi_sum, mut d_sum, mut sigma2_sum, mut cube_sum) =
+                (0, 0, 0, 0, 0);
+            for n in 0..=10000u64 {
+                if n > 0 {
+                    mu_sum += mu[n as usize] as i64;
+                    phi_sum += phi[n as usize] as i64;
+                    d_sum += d[n as usize] as i64;
+                    sigma2_sum += divisors(n).iter().map(|&d| (d * d) as i64).sum::<i64>();
+                    cube_sum += (n * n * n) as i64;
+                }
+                let f = multiplicative_prefix_sum(n, &[-1], |_, e| if e == 1 { -1 } else { 0 });
+                assert_eq!(mu_sum, f);
+                let f =
+                    multiplicative_prefix_sum(n, &[-1, 1], |p, e| (p.pow(e - 1) * (p - 1)) as i64);
+                assert_eq!(phi_sum, f);
+                let f = multiplicative_prefix_sum(n, &[2], |_, e| e as i64 + 1);
+                assert_eq!(d_sum, f);
+                let f = multiplicative_prefix_sum(n, &[1, 0, 1], |p, e| {
+                    (0..=e).map(|k| p.pow(2 * k) as i64).sum()
+                });
+                assert_eq!(sigma2_sum, f);
+                let f = multiplicative_prefix_sum(n, &[0, 0, 0, 1], |p, e| p.pow(3 * e) as i64);
+                assert_eq!(cube_sum, f);
+            }
+
+            let f =
+                multiplicative_prefix_sum(1_000_000_000, &[-1], |_, e| if e == 1 { -1 } else { 0 });
+            assert_eq!(-222, f);
+            // wraps around modulo 2^64
+            let f = multiplicative_prefix_sum(10_000_000_000, &[-1, 1], |p, e| {
+                (p.pow(e - 1) * (p - 1)) as i64
+            });
+            assert_eq!(11949611019176664750, f as u64);
+        }
+
+        #[test]
+        fn test_dirichlet_prefix_sum() {
+            let sieve = Sieve::new(10000);
+            let prefix_sum = |table: Vec<i64>| {
+                table
+                    .into_iter()
+                    .scan(0i64, |sum, x| {
+                        *sum = sum.wrapping_add(x);
+                        Some(*sum)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let mertens = prefix_sum(sieve.mobius_table().into_iter().map(|x| x as i64).collect());
+            let phi_sum = prefix_sum(
+                sieve
+                    .euler_phi_table()
+                    .into_iter()
+                    .map(|x| x as i64)
+                    .collect(),
+            );
+            let triangle = |v: u64| ((u128::from(v) * u128::from(v + 1) / 2) as u64) as i64;
+            for n in 0..=10000u64 {
+                for &len in &[0, 1, 10, 100] {
+                    let small = &mertens[..len];
+                    assert_eq!(
+                        mertens[n as usize],
+                        dirichlet_prefix_sum(n, small, |v| v as i64, |_| 1)
+                    );
+                    let small = &phi_sum[..len];
+                    assert_eq!(
+                        phi_sum[n as usize],
+                        dirichlet_prefix_sum(n, small, |v| v as i64, triangle)
+                    );
+                }
+            }
+
+            let small = &mertens[..];
+            assert_eq!(
+                -222,
+                dirichlet_prefix_sum(1_000_000_000, small, |v| v as i64, |_| 1)
+            );
+            let f = dirichlet_prefix_sum(10_000_000_000, &phi_sum, |v| v as i64, triangle);
+            assert_eq!(11949611019176664750, f as u64);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_dirichlet_prefix_sum_invalid_g() {
+            dirichlet_prefix_sum(100, &[], |v| 2 * v as i64, |v| v as i64);
+        }
+
+        #[test]
+        fn test_divisors_phi_lambda() {
+            for n in 1..1000u64 {
+                let expected = (1..=n).filter(|d| n % d == 0).collect::<Vec<_>>();
+                assert_eq!(expected, divisors(n));
+
+                let coprime = (1..=n).filter(|&k| gcd(k, n) == 1).collect::<Vec<_>>();
+                assert_eq!(coprime.len() as u64, euler_phi(n));
+
+                let lambda = (1..=n)
+                    .find(|&m| {
+                        coprime
+                            .iter()
+                            .all(|&a| pow_mod(a as _, m as _, n as _) == 1 % n as u32)
+                    })
+                    .unwrap();
+                assert_eq!(lambda, carmichael_lambda(n));
+            }
+            assert_eq!(4, divisors(4294967291 * 4294967279).len());
+            assert_eq!(
+                2 * 4 * 16 * 256 * 640 * 65536 * 6700416,
+                euler_phi(u64::max_value()),
+            );
+            assert_eq!(2u64.pow(61), euler_phi(1 << 62));
+            assert_eq!(2u64.pow(60), carmichael_lambda(1 << 62));
+        }
     }
 }
@@ -1,4 +1,4 @@
-// This code was expanded by `xtask`.
+// This code was expanded by `xtask` and has been extended by hand since. `xtask` no longer regenerates it.
 
 #![doc = " Structs that treat the modular arithmetic."]
 #![doc = ""]
@@ -59,7 +59,8 @@ mod modint {
     use super::internal_math;
     use std::{
         cell::RefCell,
-        convert::{Infallible, TryInto as _},
+        cmp,
+        collections::HashMap,
         fmt,
         hash::{Hash, Hasher},
         iter::{Product, Sum},
@@ -73,6 +74,8 @@ mod modint {
     pub type ModInt1000000007 = StaticModInt<Mod1000000007>;
     pub type ModInt998244353 = StaticModInt<Mod998244353>;
     pub type ModInt = DynamicModInt<DefaultId>;
+    pub type ModInt2305843009213693951 = StaticModInt64<Mod2305843009213693951>;
+    pub type ModInt64 = DynamicModInt64<DefaultId64>;
 
     /// Represents _ℤ/mℤ_ where _m_ is a constant value.
     ///
@@ -149,6 +152,26 @@ mod modint {
             }
         }
 
+        /// Constructs a `StaticModInt` from `val` if `val < Self::modulus()`.
+        ///
+        /// See [`ModIntBase::try_raw`] for more details.
+        ///
+        /// [`ModIntBase::try_raw`]: ./trait.ModIntBase.html#method.try_raw
+        #[inline]
+        pub fn try_raw(val: u32) -> Option<Self> {
+            <Self as ModIntBase>::try_raw(val)
+        }
+
+        /// Parses an arbitrary-length decimal integer and reduces it.
+        ///
+        /// See [`ModIntBase::checked_new`] for more details.
+        ///
+        /// [`ModIntBase::checked_new`]: ./trait.ModIntBase.html#method.checked_new
+        #[inline]
+        pub fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            <Self as ModIntBase>::checked_new(s)
+        }
+
         /// Retruns the representative.
         ///
         /// Corresponds to `atcoder::static_modint::val` in the original ACL.
@@ -179,7 +202,7 @@ mod modint {
                     panic!("attempt to divide by zero");
                 }
                 debug_assert!(
-                    internal_math::is_prime(M::VALUE.try_into().unwrap()),
+                    internal_math::is_prime_u32(M::VALUE),
                     "{} is not a prime number",
                     M::VALUE,
                 );
@@ -188,6 +211,108 @@ mod modint {
                 Self::inv_for_non_prime_modulus(self)
             }
         }
+
+        /// Returns a square root of `self` if it exists.
+        ///
+        /// See [`ModIntBase::sqrt`] for more details.
+        ///
+        /// # Constraints
+        ///
+        /// - `Self::modulus()` is a prime number.
+        ///
+        /// [`ModIntBase::sqrt`]: ./trait.ModIntBase.html#method.sqrt
+        #[inline]
+        pub fn sqrt(self) -> Option<Self> {
+            if M::HINT_VALUE_IS_PRIME {
+                debug_assert!(
+                    internal_math::is_prime_u32(M::VALUE),
+                    "{} is not a prime number",
+                    M::VALUE,
+                );
+            }
+            <Self as ModIntBase>::sqrt(self)
+        }
+    }
+
+    /// `const fn` versions of the constructors and the arithmetic.
+    ///
+    /// These are available on Rust 1.61 or later, which allows trait bounds on `const fn`s.
+    /// The Rust version is detected by `build.rs`, so they are not available if this crate is bundled into a single
+    /// source file for submission.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::ModInt998244353 as Mint;
+    ///
+    /// const INV2: Mint = Mint::from_const_u32(2).const_inv();
+    /// const POW2: [Mint; 3] = [
+    ///     Mint::from_const_u32(1),
+    ///     Mint::from_const_u32(2).const_pow(1),
+    ///     Mint::from_const_u32(2).const_pow(2),
+    /// ];
+    ///
+    /// assert_eq!(Mint::new(1), INV2 * POW2[1]);
+    /// ```
+    #[cfg(const_fn_trait_bound)]
+    impl<M: Modulus> StaticModInt<M> {
+        /// Creates a new `StaticModInt` from a `u32` in a const context.
+        ///
+        /// This is the `const fn` counterpart of [`new`].
+        ///
+        /// [`new`]: ./struct.StaticModInt.html#method.new
+        #[inline]
+        pub const fn from_const_u32(val: u32) -> Self {
+            Self {
+                val: val % M::VALUE,
+                phantom: PhantomData,
+            }
+        }
+
+        /// Returns `self` to the power of `n` in a const context.
+        ///
+        /// This is the `const fn` counterpart of [`pow`].
+        ///
+        /// [`pow`]: ./struct.StaticModInt.html#method.pow
+        pub const fn const_pow(self, mut n: u64) -> Self {
+            let m = M::VALUE as u64;
+            let mut x = self.val as u64;
+            let mut r = 1 % m;
+            while n > 0 {
+                if n & 1 == 1 {
+                    r = r * x % m;
+                }
+                x = x * x % m;
+                n >>= 1;
+            }
+            Self {
+                val: r as u32,
+                phantom: PhantomData,
+            }
+        }
+
+        /// Retruns the multiplicative inverse of `self` in a const context.
+        ///
+        /// This is the `const fn` counterpart of [`inv`] for prime moduli.
+        ///
+        /// # Constraints
+        ///
+        /// - `M::VALUE` is a prime number.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `M::HINT_VALUE_IS_PRIME` is `false` or `self` is zero.
+        ///
+        /// [`inv`]: ./struct.StaticModInt.html#method.inv
+        pub const fn const_inv(self) -> Self {
+            if !M::HINT_VALUE_IS_PRIME {
+                panic!("`const_inv` requires a prime modulus");
+            }
+            if self.val == 0 {
+                panic!("attempt to divide by zero");
+            }
+            self.const_pow(M::VALUE as u64 - 2)
+        }
     }
 
     /// These methods are implemented for the struct.
@@ -250,6 +375,10 @@ mod modint {
     /// assert_eq!(Z101::new(101), Z101::new(0));
     /// assert_eq!(Z103::new(103), Z103::new(0));
     /// ```
+    ///
+    /// On Rust 1.46 or later, [`define_static_modulus!`] writes this for you.
+    ///
+    /// [`define_static_modulus!`]: ../macro.define_static_modulus.html
     pub trait Modulus: 'static + Copy + Eq {
         const VALUE: u32;
         const HINT_VALUE_IS_PRIME: bool;
@@ -257,6 +386,62 @@ mod modint {
         fn butterfly_cache() -> &'static LocalKey<RefCell<Option<ButterflyCache<Self>>>>;
     }
 
+    /// Declares a new [`Modulus`] type.
+    ///
+    /// `HINT_VALUE_IS_PRIME` is computed at compile time by a deterministic Miller-Rabin test, and the thread-local
+    /// storage for the [`ButterflyCache`] is created automatically.
+    ///
+    /// This macro is available on Rust 1.46 or later.
+    /// The Rust version is detected by `build.rs`, so the macro is not available if this crate is bundled into a single
+    /// source file for submission.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint::define_static_modulus, Modulus, StaticModInt};
+    ///
+    /// define_static_modulus!(Mod101, 101);
+    /// define_static_modulus!(pub Mod100, 100);
+    ///
+    /// assert!(Mod101::HINT_VALUE_IS_PRIME);
+    /// assert!(!Mod100::HINT_VALUE_IS_PRIME);
+    ///
+    /// type Z101 = StaticModInt<Mod101>;
+    ///
+    /// assert_eq!(Z101::new(102), Z101::new(1));
+    /// assert_eq!(Z101::new(1), Z101::new(2) * Z101::new(2).inv());
+    /// ```
+    ///
+    /// [`Modulus`]: ./trait.Modulus.html
+    /// [`ButterflyCache`]: ./struct.ButterflyCache.html
+    #[cfg(const_fn_control_flow)]
+    #[macro_export]
+    macro_rules! define_static_modulus {
+        ($(#[$attr:meta])* $vis:vis $name:ident, $value:expr $(,)?) => {
+            $(#[$attr])*
+            #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+            $vis enum $name {}
+
+            impl $crate::Modulus for $name {
+                const VALUE: u32 = $value;
+                const HINT_VALUE_IS_PRIME: bool = $crate::__is_prime(<Self as $crate::Modulus>::VALUE);
+
+                fn butterfly_cache() -> &'static ::std::thread::LocalKey<::std::cell::RefCell<::std::option::Option<$crate::ButterflyCache<Self>>>> {
+                    thread_local! {
+                        static BUTTERFLY_CACHE: ::std::cell::RefCell<::std::option::Option<$crate::ButterflyCache<$name>>> = ::std::default::Default::default();
+                    }
+                    &BUTTERFLY_CACHE
+                }
+            }
+        };
+    }
+
+    #[cfg(const_fn_control_flow)]
+    #[doc(hidden)]
+    pub const fn __is_prime(n: u32) -> bool {
+        internal_math::is_prime_u32(n)
+    }
+
     /// Represents _1000000007_.
     #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
     pub enum Mod1000000007 {}
@@ -295,6 +480,207 @@ mod modint {
         pub sum_ie: Vec<StaticModInt<M>>,
     }
 
+    /// Represents _ℤ/mℤ_ where _m_ is a constant odd value, holding the value in the Montgomery form.
+    ///
+    /// The inner value is _xR mod m_ where _R = 2³²_, so multiplications are done without any division.
+    /// The conversion to the ordinary representative is deferred until [`val`] (or `Display`, `Hash`, ...) is called.
+    ///
+    /// # Constraints
+    ///
+    /// - [`<M as Modulus>::VALUE`] is odd
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{Mod998244353, MontgomeryModInt, StaticModInt};
+    ///
+    /// type Mint = MontgomeryModInt<Mod998244353>;
+    ///
+    /// let a = Mint::new(998_244_352);
+    /// let b = Mint::new(2);
+    /// assert_eq!(998_244_351, (a * b).val());
+    /// assert_eq!(StaticModInt::<Mod998244353>::new(998_244_351), (a * b).into());
+    /// ```
+    ///
+    /// [`val`]: ./struct.MontgomeryModInt.html#method.val
+    /// [`<M as Modulus>::VALUE`]: ../trait.Modulus.html#associatedconstant.VALUE
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    #[repr(transparent)]
+    pub struct MontgomeryModInt<M> {
+        mont: u32,
+        phantom: PhantomData<fn() -> M>,
+    }
+
+    impl<M: Modulus> MontgomeryModInt<M> {
+        /// _m⁻¹ mod 2³²_, by the Newton's method.
+        const M_INV: u32 = {
+            let m = M::VALUE;
+            let x = m; // m * m = 1 (mod 2^3)
+            let x = x.wrapping_mul(2u32.wrapping_sub(m.wrapping_mul(x))); // mod 2^6
+            let x = x.wrapping_mul(2u32.wrapping_sub(m.wrapping_mul(x))); // mod 2^12
+            let x = x.wrapping_mul(2u32.wrapping_sub(m.wrapping_mul(x))); // mod 2^24
+            x.wrapping_mul(2u32.wrapping_sub(m.wrapping_mul(x))) // mod 2^48
+        };
+
+        /// _R² mod m = 2⁶⁴ mod m_.
+        const R2: u32 = ((u64::max_value() % M::VALUE as u64 + 1) % M::VALUE as u64) as u32;
+
+        /// Returns the modulus, which is [`<M as Modulus>::VALUE`].
+        ///
+        /// [`<M as Modulus>::VALUE`]: ../trait.Modulus.html#associatedconstant.VALUE
+        #[inline(always)]
+        pub fn modulus() -> u32 {
+            M::VALUE
+        }
+
+        /// Creates a new `MontgomeryModInt`.
+        ///
+        /// Takes [any primitive integer].
+        ///
+        /// [any primitive integer]:  ../trait.RemEuclidU32.html
+        #[inline]
+        pub fn new<T: RemEuclidU32>(val: T) -> Self {
+            Self::raw(val.rem_euclid_u32(M::VALUE))
+        }
+
+        /// Constructs a `MontgomeryModInt` from a `val < Self::modulus()` without checking it.
+        ///
+        /// Unlike `StaticModInt::raw`, this performs one Montgomery multiplication to convert `val`.
+        ///
+        /// # Constraints
+        ///
+        /// - `val` is less than `Self::modulus()`
+        ///
+        /// See [`ModIntBase::raw`] for more more details.
+        ///
+        /// [`ModIntBase::raw`]: ./trait.ModIntBase.html#tymethod.raw
+        #[inline]
+        pub fn raw(val: u32) -> Self {
+            debug_assert!(M::VALUE % 2 == 1, "{} is not an odd number", M::VALUE);
+            Self::from_mont(Self::reduce(u64::from(val) * u64::from(Self::R2)))
+        }
+
+        /// Constructs a `MontgomeryModInt` from `val` if `val < Self::modulus()`.
+        ///
+        /// See [`ModIntBase::try_raw`] for more details.
+        ///
+        /// [`ModIntBase::try_raw`]: ./trait.ModIntBase.html#method.try_raw
+        #[inline]
+        pub fn try_raw(val: u32) -> Option<Self> {
+            <Self as ModIntBase>::try_raw(val)
+        }
+
+        /// Parses an arbitrary-length decimal integer and reduces it.
+        ///
+        /// See [`ModIntBase::checked_new`] for more details.
+        ///
+        /// [`ModIntBase::checked_new`]: ./trait.ModIntBase.html#method.checked_new
+        #[inline]
+        pub fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            <Self as ModIntBase>::checked_new(s)
+        }
+
+        /// Retruns the representative.
+        ///
+        /// This performs one Montgomery reduction.
+        #[inline]
+        pub fn val(self) -> u32 {
+            Self::reduce(self.mont.into())
+        }
+
+        /// Returns `self` to the power of `n`.
+        #[inline]
+        pub fn pow(self, n: u64) -> Self {
+            <Self as ModIntBase>::pow(self, n)
+        }
+
+        /// Retruns the multiplicative inverse of `self`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the multiplicative inverse does not exist.
+        #[inline]
+        pub fn inv(self) -> Self {
+            if M::HINT_VALUE_IS_PRIME {
+                if self.mont == 0 {
+                    panic!("attempt to divide by zero");
+                }
+                self.pow((M::VALUE - 2).into())
+            } else {
+                Self::inv_for_non_prime_modulus(self)
+            }
+        }
+
+        /// Returns a square root of `self` if it exists.
+        ///
+        /// See [`ModIntBase::sqrt`] for more details.
+        ///
+        /// [`ModIntBase::sqrt`]: ./trait.ModIntBase.html#method.sqrt
+        #[inline]
+        pub fn sqrt(self) -> Option<Self> {
+            <Self as ModIntBase>::sqrt(self)
+        }
+
+        #[inline]
+        fn from_mont(mont: u32) -> Self {
+            Self {
+                mont,
+                phantom: PhantomData,
+            }
+        }
+
+        /// Calculates _tR⁻¹ mod m_ for _t < mR_.
+        #[inline]
+        fn reduce(t: u64) -> u32 {
+            let m = u64::from((t as u32).wrapping_mul(Self::M_INV)) * u64::from(M::VALUE);
+            // `t` and `m` have the same lower 32 bits.
+            let (val, borrowed) = ((t >> 32) as u32).overflowing_sub((m >> 32) as u32);
+            if borrowed {
+                val.wrapping_add(M::VALUE)
+            } else {
+                val
+            }
+        }
+    }
+
+    /// These methods are implemented for the struct.
+    /// You don't need to `use` `ModIntBase` to call methods of `MontgomeryModInt`.
+    impl<M: Modulus> ModIntBase for MontgomeryModInt<M> {
+        #[inline(always)]
+        fn modulus() -> u32 {
+            Self::modulus()
+        }
+
+        #[inline]
+        fn raw(val: u32) -> Self {
+            Self::raw(val)
+        }
+
+        #[inline]
+        fn val(self) -> u32 {
+            self.val()
+        }
+
+        #[inline]
+        fn inv(self) -> Self {
+            self.inv()
+        }
+    }
+
+    impl<M: Modulus> From<StaticModInt<M>> for MontgomeryModInt<M> {
+        #[inline]
+        fn from(from: StaticModInt<M>) -> Self {
+            Self::raw(from.val())
+        }
+    }
+
+    impl<M: Modulus> From<MontgomeryModInt<M>> for StaticModInt<M> {
+        #[inline]
+        fn from(from: MontgomeryModInt<M>) -> Self {
+            Self::raw(from.val())
+        }
+    }
+
     /// Represents _ℤ/mℤ_ where _m_ is a dynamic value.
     ///
     /// Corresponds to `atcoder::dynamic_modint` in the original ACL.
@@ -366,6 +752,38 @@ mod modint {
             I::companion_barrett().update(modulus);
         }
 
+        /// Sets a modulus, calls `f`, and then restores the previous modulus.
+        ///
+        /// The previous modulus is restored even if `f` panics.
+        ///
+        /// Note that the modulus is still shared among threads unless `I` is declared as `thread_local` with [`define_id!`].
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::ModInt as Mint;
+        ///
+        /// Mint::set_modulus(7);
+        /// let x = Mint::with_modulus(11, || Mint::new(12).val());
+        /// assert_eq!(1, x);
+        /// assert_eq!(7, Mint::modulus());
+        /// ```
+        ///
+        /// [`define_id!`]: ../macro.define_id.html
+        pub fn with_modulus<R, F: FnOnce() -> R>(modulus: u32, f: F) -> R {
+            struct Restore<I: Id>(u32, PhantomData<fn() -> I>);
+
+            impl<I: Id> Drop for Restore<I> {
+                fn drop(&mut self) {
+                    I::companion_barrett().update(self.0);
+                }
+            }
+
+            let _restore = Restore::<I>(Self::modulus(), PhantomData);
+            Self::set_modulus(modulus);
+            f()
+        }
+
         /// Creates a new `DynamicModInt`.
         ///
         /// Takes [any primitive integer].
@@ -397,6 +815,26 @@ mod modint {
             }
         }
 
+        /// Constructs a `DynamicModInt` from `val` if `val < Self::modulus()`.
+        ///
+        /// See [`ModIntBase::try_raw`] for more details.
+        ///
+        /// [`ModIntBase::try_raw`]: ./trait.ModIntBase.html#method.try_raw
+        #[inline]
+        pub fn try_raw(val: u32) -> Option<Self> {
+            <Self as ModIntBase>::try_raw(val)
+        }
+
+        /// Parses an arbitrary-length decimal integer and reduces it.
+        ///
+        /// See [`ModIntBase::checked_new`] for more details.
+        ///
+        /// [`ModIntBase::checked_new`]: ./trait.ModIntBase.html#method.checked_new
+        #[inline]
+        pub fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            <Self as ModIntBase>::checked_new(s)
+        }
+
         /// Retruns the representative.
         ///
         /// Corresponds to `atcoder::static_modint::val` in the original ACL.
@@ -424,6 +862,16 @@ mod modint {
         pub fn inv(self) -> Self {
             Self::inv_for_non_prime_modulus(self)
         }
+
+        /// Returns a square root of `self` if it exists.
+        ///
+        /// See [`ModIntBase::sqrt`] for more details.
+        ///
+        /// [`ModIntBase::sqrt`]: ./trait.ModIntBase.html#method.sqrt
+        #[inline]
+        pub fn sqrt(self) -> Option<Self> {
+            <Self as ModIntBase>::sqrt(self)
+        }
     }
 
     /// These methods are implemented for the struct.
@@ -450,10 +898,87 @@ mod modint {
         }
     }
 
+    /// Represents an identifier of the modulus of a [`DynamicModInt`].
+    ///
+    /// Use [`define_id!`] to declare a new one.
+    ///
+    /// [`DynamicModInt`]: ./struct.DynamicModInt.html
+    /// [`define_id!`]: ../macro.define_id.html
     pub trait Id: 'static + Copy + Eq {
         fn companion_barrett() -> &'static Barrett;
     }
 
+    /// Declares new [`Id`] types.
+    ///
+    /// - `static` ones share one modulus among all of the threads, like [`DefaultId`].
+    /// - `thread_local` ones have a modulus for each thread, which starts from _998244353_.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint::define_id, DynamicModInt};
+    /// use std::thread;
+    ///
+    /// define_id! {
+    ///     /// Shared among the threads.
+    ///     pub static GlobalId;
+    ///     /// Independent for each thread.
+    ///     pub thread_local LocalId;
+    /// }
+    ///
+    /// DynamicModInt::<GlobalId>::set_modulus(7);
+    /// DynamicModInt::<LocalId>::set_modulus(11);
+    ///
+    /// thread::spawn(|| {
+    ///     assert_eq!(7, DynamicModInt::<GlobalId>::modulus());
+    ///     assert_eq!(998_244_353, DynamicModInt::<LocalId>::modulus());
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// assert_eq!(11, DynamicModInt::<LocalId>::modulus());
+    /// ```
+    ///
+    /// [`Id`]: ./trait.Id.html
+    /// [`DefaultId`]: ./enum.DefaultId.html
+    #[macro_export]
+    macro_rules! define_id {
+        () => {};
+        ($(#[$attr:meta])* $vis:vis static $name:ident; $($rest:tt)*) => {
+            $(#[$attr])*
+            #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+            $vis enum $name {}
+
+            impl $crate::Id for $name {
+                fn companion_barrett() -> &'static $crate::Barrett {
+                    static BARRETT: $crate::Barrett = $crate::Barrett::new(998_244_353);
+                    &BARRETT
+                }
+            }
+
+            $crate::define_id!($($rest)*);
+        };
+        ($(#[$attr:meta])* $vis:vis thread_local $name:ident; $($rest:tt)*) => {
+            $(#[$attr])*
+            #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+            $vis enum $name {}
+
+            impl $crate::Id for $name {
+                fn companion_barrett() -> &'static $crate::Barrett {
+                    // Leaks one `Barrett` for each thread.
+                    thread_local! {
+                        static BARRETT: &'static $crate::Barrett = ::std::boxed::Box::leak(
+                            ::std::boxed::Box::new($crate::Barrett::new(998_244_353)),
+                        );
+                    }
+                    BARRETT.with(|&barrett| barrett)
+                }
+            }
+
+            $crate::define_id!($($rest)*);
+        };
+    }
+
     #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
     pub enum DefaultId {}
 
@@ -512,147 +1037,518 @@ mod modint {
         }
     }
 
-    /// A trait for [`StaticModInt`] and [`DynamicModInt`].
+    /// Represents _ℤ/mℤ_ where _m_ is a constant 64-bit value.
     ///
-    /// Corresponds to `atcoder::internal::modint_base` in the original ACL.
+    /// This is the 64-bit counterpart of [`StaticModInt`]. Multiplications are done with a 128-bit Barrett reduction.
     ///
-    /// [`StaticModInt`]: ../struct.StaticModInt.html
-    /// [`DynamicModInt`]: ../struct.DynamicModInt.html
-    pub trait ModIntBase:
-        Default
-        + FromStr
-        + From<i8>
-        + From<i16>
-        + From<i32>
-        + From<i64>
-        + From<i128>
-        + From<isize>
-        + From<u8>
-        + From<u16>
-        + From<u32>
-        + From<u64>
-        + From<u128>
-        + From<usize>
-        + Copy
-        + Eq
-        + Hash
-        + fmt::Display
-        + fmt::Debug
-        + Neg<Output = Self>
-        + Add<Output = Self>
-        + Sub<Output = Self>
-        + Mul<Output = Self>
-        + Div<Output = Self>
-        + AddAssign
-        + SubAssign
-        + MulAssign
-        + DivAssign
-    {
-        /// Returns the modulus.
-        ///
-        /// Corresponds to `atcoder::static_modint::mod` and `atcoder::dynamic_modint::mod` in the original ACL.
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::ModInt2305843009213693951 as Mint;
+    ///
+    /// let a = Mint::new(1u64 << 60);
+    /// assert_eq!(1, (a * 2).val());
+    /// ```
+    ///
+    /// [`StaticModInt`]: ./struct.StaticModInt.html
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    #[repr(transparent)]
+    pub struct StaticModInt64<M> {
+        val: u64,
+        phantom: PhantomData<fn() -> M>,
+    }
+
+    impl<M: Modulus64> StaticModInt64<M> {
+        /// _floor((2¹²⁸ - 1)/m)_.
+        const IM: u128 = u128::max_value() / M::VALUE as u128;
+
+        /// Returns the modulus, which is [`<M as Modulus64>::VALUE`].
         ///
-        /// # Example
+        /// [`<M as Modulus64>::VALUE`]: ../trait.Modulus64.html#associatedconstant.VALUE
+        #[inline(always)]
+        pub fn modulus() -> u64 {
+            M::VALUE
+        }
+
+        /// Creates a new `StaticModInt64`.
         ///
-        /// ```
-        /// use ac_library_rs::modint::ModIntBase;
+        /// Takes [any primitive integer].
         ///
-        /// fn f<Z: ModIntBase>() {
-        ///     let _: u32 = Z::modulus();
-        /// }
-        /// ```
-        fn modulus() -> u32;
+        /// [any primitive integer]:  ../trait.RemEuclidU64.html
+        #[inline]
+        pub fn new<T: RemEuclidU64>(val: T) -> Self {
+            Self::raw(val.rem_euclid_u64(M::VALUE))
+        }
 
-        /// Constructs a `Self` from a `val < Self::modulus()` without checking it.
-        ///
-        /// Corresponds to `atcoder::static_modint::raw` and `atcoder::dynamic_modint::raw` in the original ACL.
+        /// Constructs a `StaticModInt64` from a `val < Self::modulus()` without checking it.
         ///
         /// # Constraints
         ///
         /// - `val` is less than `Self::modulus()`
         ///
-        /// **Note that all operations assume that inner values are smaller than the modulus.**
-        /// If `val` is greater than or equal to `Self::modulus()`, the behaviors are not defined.
-        ///
-        /// ```should_panic
-        /// use ac_library_rs::ModInt1000000007 as Mint;
-        ///
-        /// let x = Mint::raw(1_000_000_007);
-        /// let y = x + x;
-        /// assert_eq!(0, y.val());
-        /// ```
+        /// See [`ModIntBase::raw`] for more more details.
         ///
-        /// ```text
-        /// thread 'main' panicked at 'assertion failed: `(left == right)`
-        ///   left: `0`,
-        ///  right: `1000000007`', src/modint.rs:8:1
-        /// note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
-        /// ```
+        /// [`ModIntBase::raw`]: ./trait.ModIntBase.html#tymethod.raw
+        #[inline]
+        pub fn raw(val: u64) -> Self {
+            Self {
+                val,
+                phantom: PhantomData,
+            }
+        }
+
+        /// Constructs a `StaticModInt64` from `val` if `val < Self::modulus()`.
         ///
-        /// # Example
+        /// See [`ModIntBase64::try_raw`] for more details.
         ///
-        /// ```
-        /// use ac_library_rs::modint::ModIntBase;
+        /// [`ModIntBase64::try_raw`]: ./trait.ModIntBase64.html#method.try_raw
+        #[inline]
+        pub fn try_raw(val: u64) -> Option<Self> {
+            <Self as ModIntBase64>::try_raw(val)
+        }
+
+        /// Parses an arbitrary-length decimal integer and reduces it.
         ///
-        /// fn f<Z: ModIntBase>() -> Z {
-        ///     debug_assert!(Z::modulus() >= 100);
+        /// See [`ModIntBase64::checked_new`] for more details.
         ///
-        ///     let mut acc = Z::new(0);
-        ///     for i in 0..100 {
-        ///         if i % 3 == 0 {
-        ///             // I know `i` is smaller than the modulus!
-        ///             acc += Z::raw(i);
-        ///         }
-        ///     }
-        ///     acc
-        /// }
-        /// ```
-        fn raw(val: u32) -> Self;
+        /// [`ModIntBase64::checked_new`]: ./trait.ModIntBase64.html#method.checked_new
+        #[inline]
+        pub fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            <Self as ModIntBase64>::checked_new(s)
+        }
 
         /// Retruns the representative.
-        ///
-        /// Corresponds to `atcoder::static_modint::val` and `atcoder::dynamic_modint::val` in the original ACL.
-        ///
-        /// # Example
-        ///
-        /// ```
-        /// use ac_library_rs::modint::ModIntBase;
-        ///
-        /// fn f<Z: ModIntBase>(x: Z) {
-        ///     let _: u32 = x.val();
-        /// }
-        /// ```
-        fn val(self) -> u32;
+        #[inline]
+        pub fn val(self) -> u64 {
+            self.val
+        }
+
+        /// Returns `self` to the power of `n`.
+        #[inline]
+        pub fn pow(self, n: u64) -> Self {
+            <Self as ModIntBase64>::pow(self, n)
+        }
 
         /// Retruns the multiplicative inverse of `self`.
         ///
-        /// Corresponds to `atcoder::static_modint::inv` and `atcoder::dynamic_modint::inv` in the original ACL.
-        ///
         /// # Panics
         ///
         /// Panics if the multiplicative inverse does not exist.
+        #[inline]
+        pub fn inv(self) -> Self {
+            if M::HINT_VALUE_IS_PRIME {
+                if self.val() == 0 {
+                    panic!("attempt to divide by zero");
+                }
+                self.pow(M::VALUE - 2)
+            } else {
+                Self::inv_for_non_prime_modulus(self)
+            }
+        }
+    }
+
+    /// These methods are implemented for the struct.
+    /// You don't need to `use` `ModIntBase64` to call methods of `StaticModInt64`.
+    impl<M: Modulus64> ModIntBase64 for StaticModInt64<M> {
+        #[inline(always)]
+        fn modulus() -> u64 {
+            Self::modulus()
+        }
+
+        #[inline]
+        fn raw(val: u64) -> Self {
+            Self::raw(val)
+        }
+
+        #[inline]
+        fn val(self) -> u64 {
+            self.val()
+        }
+
+        #[inline]
+        fn inv(self) -> Self {
+            self.inv()
+        }
+    }
+
+    /// Represents a 64-bit modulus.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::modint::{Modulus64, StaticModInt64};
+    ///
+    /// #[derive(Copy, Clone, Eq, PartialEq)]
+    /// enum Mod1000000000000000003 {}
+    ///
+    /// impl Modulus64 for Mod1000000000000000003 {
+    ///     const VALUE: u64 = 1_000_000_000_000_000_003;
+    ///     const HINT_VALUE_IS_PRIME: bool = true;
+    /// }
+    ///
+    /// type Z = StaticModInt64<Mod1000000000000000003>;
+    ///
+    /// assert_eq!(Z::new(1_000_000_000_000_000_003u64), Z::new(0));
+    /// ```
+    pub trait Modulus64: 'static + Copy + Eq {
+        const VALUE: u64;
+        const HINT_VALUE_IS_PRIME: bool;
+    }
+
+    /// Represents _2305843009213693951 = 2⁶¹ - 1_.
+    #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+    pub enum Mod2305843009213693951 {}
+
+    impl Modulus64 for Mod2305843009213693951 {
+        const VALUE: u64 = (1 << 61) - 1;
+        const HINT_VALUE_IS_PRIME: bool = true;
+    }
+
+    /// Represents _ℤ/mℤ_ where _m_ is a dynamic 64-bit value.
+    ///
+    /// This is the 64-bit counterpart of [`DynamicModInt`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::ModInt64 as Mint;
+    ///
+    /// Mint::set_modulus(10_000_000_000);
+    /// let a = Mint::new(9_999_999_999u64);
+    ///
+    /// assert_eq!(9_999_999_998, (a * 2).val());
+    /// ```
+    ///
+    /// [`DynamicModInt`]: ./struct.DynamicModInt.html
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    #[repr(transparent)]
+    pub struct DynamicModInt64<I> {
+        val: u64,
+        phantom: PhantomData<fn() -> I>,
+    }
+
+    impl<I: Id64> DynamicModInt64<I> {
+        /// Returns the modulus.
+        #[inline]
+        pub fn modulus() -> u64 {
+            I::companion_barrett().umod()
+        }
+
+        /// Sets a modulus.
         ///
-        /// # Example
-        ///
-        /// ```
-        /// use ac_library_rs::modint::ModIntBase;
+        /// # Constraints
         ///
-        /// fn f<Z: ModIntBase>(x: Z) {
-        ///     let _: Z = x.inv();
-        /// }
-        /// ```
-        fn inv(self) -> Self;
+        /// - This function must be called earlier than any other operation of `Self`.
+        #[inline]
+        pub fn set_modulus(modulus: u64) {
+            if modulus == 0 {
+                panic!("the modulus must not be 0");
+            }
+            I::companion_barrett().update(modulus);
+        }
 
-        /// Creates a new `Self`.
+        /// Creates a new `DynamicModInt64`.
         ///
         /// Takes [any primitive integer].
         ///
-        /// # Example
+        /// [any primitive integer]:  ../trait.RemEuclidU64.html
+        #[inline]
+        pub fn new<T: RemEuclidU64>(val: T) -> Self {
+            <Self as ModIntBase64>::new(val)
+        }
+
+        /// Constructs a `DynamicModInt64` from a `val < Self::modulus()` without checking it.
         ///
-        /// ```
-        /// use ac_library_rs::modint::ModIntBase;
+        /// # Constraints
         ///
-        /// fn f<Z: ModIntBase>() {
+        /// - `val` is less than `Self::modulus()`
+        ///
+        /// See [`ModIntBase::raw`] for more more details.
+        ///
+        /// [`ModIntBase::raw`]: ./trait.ModIntBase.html#tymethod.raw
+        #[inline]
+        pub fn raw(val: u64) -> Self {
+            Self {
+                val,
+                phantom: PhantomData,
+            }
+        }
+
+        /// Constructs a `DynamicModInt64` from `val` if `val < Self::modulus()`.
+        ///
+        /// See [`ModIntBase64::try_raw`] for more details.
+        ///
+        /// [`ModIntBase64::try_raw`]: ./trait.ModIntBase64.html#method.try_raw
+        #[inline]
+        pub fn try_raw(val: u64) -> Option<Self> {
+            <Self as ModIntBase64>::try_raw(val)
+        }
+
+        /// Parses an arbitrary-length decimal integer and reduces it.
+        ///
+        /// See [`ModIntBase64::checked_new`] for more details.
+        ///
+        /// [`ModIntBase64::checked_new`]: ./trait.ModIntBase64.html#method.checked_new
+        #[inline]
+        pub fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            <Self as ModIntBase64>::checked_new(s)
+        }
+
+        /// Retruns the representative.
+        #[inline]
+        pub fn val(self) -> u64 {
+            self.val
+        }
+
+        /// Returns `self` to the power of `n`.
+        #[inline]
+        pub fn pow(self, n: u64) -> Self {
+            <Self as ModIntBase64>::pow(self, n)
+        }
+
+        /// Retruns the multiplicative inverse of `self`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the multiplicative inverse does not exist.
+        #[inline]
+        pub fn inv(self) -> Self {
+            Self::inv_for_non_prime_modulus(self)
+        }
+    }
+
+    /// These methods are implemented for the struct.
+    /// You don't need to `use` `ModIntBase64` to call methods of `DynamicModInt64`.
+    impl<I: Id64> ModIntBase64 for DynamicModInt64<I> {
+        #[inline]
+        fn modulus() -> u64 {
+            Self::modulus()
+        }
+
+        #[inline]
+        fn raw(val: u64) -> Self {
+            Self::raw(val)
+        }
+
+        #[inline]
+        fn val(self) -> u64 {
+            self.val()
+        }
+
+        #[inline]
+        fn inv(self) -> Self {
+            self.inv()
+        }
+    }
+
+    pub trait Id64: 'static + Copy + Eq {
+        fn companion_barrett() -> &'static Barrett64;
+    }
+
+    #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+    pub enum DefaultId64 {}
+
+    impl Id64 for DefaultId64 {
+        fn companion_barrett() -> &'static Barrett64 {
+            static BARRETT: Barrett64 = Barrett64::default();
+            &BARRETT
+        }
+    }
+
+    /// Pair of _m_ and _floor((2¹²⁸ - 1)/m)_.
+    pub struct Barrett64 {
+        m: AtomicU64,
+        im_hi: AtomicU64,
+        im_lo: AtomicU64,
+    }
+
+    impl Barrett64 {
+        /// Creates a new `Barrett64`.
+        #[inline]
+        pub const fn new(m: u64) -> Self {
+            Self {
+                m: AtomicU64::new(m),
+                im_hi: AtomicU64::new(((u128::max_value() / m as u128) >> 64) as u64),
+                im_lo: AtomicU64::new((u128::max_value() / m as u128) as u64),
+            }
+        }
+
+        #[inline]
+        const fn default() -> Self {
+            Self::new(998_244_353)
+        }
+
+        #[inline]
+        fn update(&self, m: u64) {
+            let im = u128::max_value() / u128::from(m);
+            self.m.store(m, atomic::Ordering::SeqCst);
+            self.im_hi
+                .store((im >> 64) as u64, atomic::Ordering::SeqCst);
+            self.im_lo.store(im as u64, atomic::Ordering::SeqCst);
+        }
+
+        #[inline]
+        fn umod(&self) -> u64 {
+            self.m.load(atomic::Ordering::SeqCst)
+        }
+
+        #[inline]
+        fn mul(&self, a: u64, b: u64) -> u64 {
+            let m = self.m.load(atomic::Ordering::SeqCst);
+            let im_hi = self.im_hi.load(atomic::Ordering::SeqCst);
+            let im_lo = self.im_lo.load(atomic::Ordering::SeqCst);
+            internal_math::mul_mod_u64(a, b, m, u128::from(im_hi) << 64 | u128::from(im_lo))
+        }
+    }
+
+    impl Default for Barrett64 {
+        #[inline]
+        fn default() -> Self {
+            Self::default()
+        }
+    }
+
+    /// A trait for [`StaticModInt`] and [`DynamicModInt`].
+    ///
+    /// Corresponds to `atcoder::internal::modint_base` in the original ACL.
+    ///
+    /// [`StaticModInt`]: ../struct.StaticModInt.html
+    /// [`DynamicModInt`]: ../struct.DynamicModInt.html
+    pub trait ModIntBase:
+        Default
+        + FromStr<Err = ParseModIntError>
+        + From<i8>
+        + From<i16>
+        + From<i32>
+        + From<i64>
+        + From<i128>
+        + From<isize>
+        + From<u8>
+        + From<u16>
+        + From<u32>
+        + From<u64>
+        + From<u128>
+        + From<usize>
+        + Copy
+        + Eq
+        + Hash
+        + fmt::Display
+        + fmt::Debug
+        + Neg<Output = Self>
+        + Add<Output = Self>
+        + Sub<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + AddAssign
+        + SubAssign
+        + MulAssign
+        + DivAssign
+    {
+        /// Returns the modulus.
+        ///
+        /// Corresponds to `atcoder::static_modint::mod` and `atcoder::dynamic_modint::mod` in the original ACL.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::modint::ModIntBase;
+        ///
+        /// fn f<Z: ModIntBase>() {
+        ///     let _: u32 = Z::modulus();
+        /// }
+        /// ```
+        fn modulus() -> u32;
+
+        /// Constructs a `Self` from a `val < Self::modulus()` without checking it.
+        ///
+        /// Corresponds to `atcoder::static_modint::raw` and `atcoder::dynamic_modint::raw` in the original ACL.
+        ///
+        /// # Constraints
+        ///
+        /// - `val` is less than `Self::modulus()`
+        ///
+        /// **Note that all operations assume that inner values are smaller than the modulus.**
+        /// If `val` is greater than or equal to `Self::modulus()`, the behaviors are not defined.
+        ///
+        /// ```should_panic
+        /// use ac_library_rs::ModInt1000000007 as Mint;
+        ///
+        /// let x = Mint::raw(1_000_000_007);
+        /// let y = x + x;
+        /// assert_eq!(0, y.val());
+        /// ```
+        ///
+        /// ```text
+        /// thread 'main' panicked at 'assertion failed: `(left == right)`
+        ///   left: `0`,
+        ///  right: `1000000007`', src/modint.rs:8:1
+        /// note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+        /// ```
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::modint::ModIntBase;
+        ///
+        /// fn f<Z: ModIntBase>() -> Z {
+        ///     debug_assert!(Z::modulus() >= 100);
+        ///
+        ///     let mut acc = Z::new(0);
+        ///     for i in 0..100 {
+        ///         if i % 3 == 0 {
+        ///             // I know `i` is smaller than the modulus!
+        ///             acc += Z::raw(i);
+        ///         }
+        ///     }
+        ///     acc
+        /// }
+        /// ```
+        fn raw(val: u32) -> Self;
+
+        /// Retruns the representative.
+        ///
+        /// Corresponds to `atcoder::static_modint::val` and `atcoder::dynamic_modint::val` in the original ACL.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::modint::ModIntBase;
+        ///
+        /// fn f<Z: ModIntBase>(x: Z) {
+        ///     let _: u32 = x.val();
+        /// }
+        /// ```
+        fn val(self) -> u32;
+
+        /// Retruns the multiplicative inverse of `self`.
+        ///
+        /// Corresponds to `atcoder::static_modint::inv` and `atcoder::dynamic_modint::inv` in the original ACL.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the multiplicative inverse does not exist.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::modint::ModIntBase;
+        ///
+        /// fn f<Z: ModIntBase>(x: Z) {
+        ///     let _: Z = x.inv();
+        /// }
+        /// ```
+        fn inv(self) -> Self;
+
+        /// Creates a new `Self`.
+        ///
+        /// Takes [any primitive integer].
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::modint::ModIntBase;
+        ///
+        /// fn f<Z: ModIntBase>() {
         ///     let _ = Z::new(1u32);
         ///     let _ = Z::new(1usize);
         ///     let _ = Z::new(-1i64);
@@ -665,19 +1561,252 @@ mod modint {
             Self::raw(val.rem_euclid_u32(Self::modulus()))
         }
 
+        /// Constructs a `Self` from `val` if `val < Self::modulus()`.
+        ///
+        /// This is a checked version of [`raw`].
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::ModInt1000000007 as Mint;
+        ///
+        /// assert_eq!(Some(Mint::new(1_000_000_006)), Mint::try_raw(1_000_000_006));
+        /// assert_eq!(None, Mint::try_raw(1_000_000_007));
+        /// ```
+        ///
+        /// [`raw`]: ./trait.ModIntBase.html#tymethod.raw
+        #[inline]
+        fn try_raw(val: u32) -> Option<Self> {
+            if val < Self::modulus() {
+                Some(Self::raw(val))
+            } else {
+                None
+            }
+        }
+
+        /// Parses a decimal integer of arbitrary length and reduces it digit by digit.
+        ///
+        /// The string may start with `+` or `-`. `FromStr` for modints is implemented with this function.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ParseModIntError`] if `s` is empty or contains a character that is not an ASCII digit.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(n)_ where _n_ is the length of `s`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::ModInt1000000007 as Mint;
+        ///
+        /// // 10^20 mod 1000000007
+        /// assert_eq!(Ok(Mint::new(4900)), Mint::checked_new("100000000000000000000"));
+        /// assert_eq!(Ok(-Mint::new(4900)), Mint::checked_new("-100000000000000000000"));
+        /// assert!(Mint::checked_new("").is_err());
+        /// assert!(Mint::checked_new("1e9").is_err());
+        /// ```
+        ///
+        /// [`ParseModIntError`]: ../struct.ParseModIntError.html
+        #[inline]
+        fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            let (neg, rem) = parse_decimal(s, Self::modulus().into())?;
+            let x = Self::raw(rem as _);
+            Ok(if neg { -x } else { x })
+        }
+
         /// Returns `self` to the power of `n`.
         ///
-        /// Corresponds to `atcoder::static_modint::pow` and `atcoder::dynamic_modint::pow` in the original ACL.
+        /// Corresponds to `atcoder::static_modint::pow` and `atcoder::dynamic_modint::pow` in the original ACL.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use ac_library_rs::modint::ModIntBase;
+        ///
+        /// fn f<Z: ModIntBase>() {
+        ///     let _: Z = Z::new(2).pow(3);
+        /// }
+        /// ```
+        #[inline]
+        fn pow(self, mut n: u64) -> Self {
+            let mut x = self;
+            let mut r = Self::raw(1);
+            while n > 0 {
+                if n & 1 == 1 {
+                    r *= x;
+                }
+                x *= x;
+                n >>= 1;
+            }
+            r
+        }
+
+        /// Returns a square root of `self` if it exists.
+        ///
+        /// If there are two square roots, the smaller one (in terms of [`val`]) is returned.
+        ///
+        /// # Constraints
+        ///
+        /// - The modulus is a prime number.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the modulus turns out to be a composite number during the computation.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(log² m)_ (Tonelli–Shanks)
         ///
         /// # Example
         ///
         /// ```
         /// use ac_library_rs::modint::ModIntBase;
+        /// use ac_library_rs::ModInt1000000007 as Mint;
         ///
-        /// fn f<Z: ModIntBase>() {
-        ///     let _: Z = Z::new(2).pow(3);
+        /// fn f<Z: ModIntBase>(x: Z) -> Option<Z> {
+        ///     x.sqrt()
         /// }
+        ///
+        /// assert_eq!(Some(Mint::new(3)), f(Mint::new(9)));
+        /// assert_eq!(None, f(Mint::new(5)));
         /// ```
+        ///
+        /// [`val`]: ./trait.ModIntBase.html#tymethod.val
+        fn sqrt(self) -> Option<Self> {
+            let p = Self::modulus();
+            if p == 2 || self.val() == 0 {
+                return Some(self);
+            }
+            let one = Self::raw(1);
+            if self.pow(((p - 1) / 2).into()) != one {
+                return None;
+            }
+
+            // p - 1 = q * 2^s
+            let s = (p - 1).trailing_zeros();
+            let q = (p - 1) >> s;
+            let z = (2..p)
+                .map(Self::raw)
+                .find(|z| z.pow(((p - 1) / 2).into()) == -one)
+                .unwrap_or_else(|| panic!("{} is not a prime number", p));
+
+            // Invariants: c^(2^(m - 1)) = -1, t^(2^(m - 1)) = 1, r^2 = t * self
+            let mut m = s;
+            let mut c = z.pow(q.into());
+            let mut t = self.pow(q.into());
+            let mut r = self.pow(((q + 1) / 2).into());
+            while t != one {
+                let mut i = 1;
+                let mut t2i = t * t;
+                while t2i != one {
+                    t2i *= t2i;
+                    i += 1;
+                    if i == m {
+                        panic!("{} is not a prime number", p);
+                    }
+                }
+                let b = c.pow(1 << (m - i - 1));
+                m = i;
+                c = b * b;
+                t *= c;
+                r *= b;
+            }
+            Some(if r.val() <= (-r).val() { r } else { -r })
+        }
+    }
+
+    /// A trait for [`StaticModInt64`] and [`DynamicModInt64`].
+    ///
+    /// This is the 64-bit counterpart of [`ModIntBase`].
+    ///
+    /// [`StaticModInt64`]: ../struct.StaticModInt64.html
+    /// [`DynamicModInt64`]: ../struct.DynamicModInt64.html
+    /// [`ModIntBase`]: ../trait.ModIntBase.html
+    pub trait ModIntBase64:
+        Default
+        + FromStr<Err = ParseModIntError>
+        + From<i8>
+        + From<i16>
+        + From<i32>
+        + From<i64>
+        + From<i128>
+        + From<isize>
+        + From<u8>
+        + From<u16>
+        + From<u32>
+        + From<u64>
+        + From<u128>
+        + From<usize>
+        + Copy
+        + Eq
+        + Hash
+        + fmt::Display
+        + fmt::Debug
+        + Neg<Output = Self>
+        + Add<Output = Self>
+        + Sub<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + AddAssign
+        + SubAssign
+        + MulAssign
+        + DivAssign
+    {
+        /// Returns the modulus.
+        fn modulus() -> u64;
+
+        /// Constructs a `Self` from a `val < Self::modulus()` without checking it.
+        ///
+        /// # Constraints
+        ///
+        /// - `val` is less than `Self::modulus()`
+        fn raw(val: u64) -> Self;
+
+        /// Retruns the representative.
+        fn val(self) -> u64;
+
+        /// Retruns the multiplicative inverse of `self`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the multiplicative inverse does not exist.
+        fn inv(self) -> Self;
+
+        /// Creates a new `Self`.
+        ///
+        /// Takes [any primitive integer].
+        ///
+        /// [any primitive integer]:  ../trait.RemEuclidU64.html
+        #[inline]
+        fn new<T: RemEuclidU64>(val: T) -> Self {
+            Self::raw(val.rem_euclid_u64(Self::modulus()))
+        }
+
+        /// Constructs a `Self` from `val` if `val < Self::modulus()`.
+        #[inline]
+        fn try_raw(val: u64) -> Option<Self> {
+            if val < Self::modulus() {
+                Some(Self::raw(val))
+            } else {
+                None
+            }
+        }
+
+        /// Parses a decimal integer of arbitrary length and reduces it digit by digit.
+        ///
+        /// See [`ModIntBase::checked_new`] for more details.
+        ///
+        /// [`ModIntBase::checked_new`]: ./trait.ModIntBase.html#method.checked_new
+        #[inline]
+        fn checked_new(s: &str) -> Result<Self, ParseModIntError> {
+            let (neg, rem) = parse_decimal(s, Self::modulus())?;
+            let x = Self::raw(rem);
+            Ok(if neg { -x } else { x })
+        }
+
+        /// Returns `self` to the power of `n`.
         #[inline]
         fn pow(self, mut n: u64) -> Self {
             let mut x = self;
@@ -693,77 +1822,301 @@ mod modint {
         }
     }
 
-    /// A trait for `{StaticModInt, DynamicModInt, ModIntBase}::new`.
-    pub trait RemEuclidU32 {
-        /// Calculates `self` _mod_ `modulus` losslessly.
-        fn rem_euclid_u32(self, modulus: u32) -> u32;
+    /// An error which can be returned when parsing a modint.
+    ///
+    /// This error is used as the error type for the `FromStr` implementations of the modints and for
+    /// [`ModIntBase::checked_new`].
+    ///
+    /// [`ModIntBase::checked_new`]: ./trait.ModIntBase.html#method.checked_new
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ParseModIntError {
+        kind: ParseModIntErrorKind,
+    }
+
+    impl ParseModIntError {
+        /// Returns the detailed cause of parsing a modint failing.
+        #[inline]
+        pub fn kind(&self) -> &ParseModIntErrorKind {
+            &self.kind
+        }
+    }
+
+    impl fmt::Display for ParseModIntError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self.kind {
+                ParseModIntErrorKind::Empty => "cannot parse integer from empty string",
+                ParseModIntErrorKind::InvalidDigit => "invalid digit found in string",
+            })
+        }
+    }
+
+    impl std::error::Error for ParseModIntError {}
+
+    /// Enum to store the various types of errors that can cause parsing a modint to fail.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ParseModIntErrorKind {
+        /// Value being parsed is empty.
+        Empty,
+        /// Contains an invalid digit, including a sign without any digits.
+        InvalidDigit,
+    }
+
+    /// Parses `s` as a decimal integer and returns its sign and its absolute value modulo `modulus`.
+    fn parse_decimal(s: &str, modulus: u64) -> Result<(bool, u64), ParseModIntError> {
+        if s.is_empty() {
+            return Err(ParseModIntError {
+                kind: ParseModIntErrorKind::Empty,
+            });
+        }
+        let (neg, digits) = match s.as_bytes()[0] {
+            b'+' => (false, &s[1..]),
+            b'-' => (true, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() {
+            return Err(ParseModIntError {
+                kind: ParseModIntErrorKind::InvalidDigit,
+            });
+        }
+        let mut rem = 0;
+        for c in digits.bytes() {
+            if !c.is_ascii_digit() {
+                return Err(ParseModIntError {
+                    kind: ParseModIntErrorKind::InvalidDigit,
+                });
+            }
+            rem = ((u128::from(rem) * 10 + u128::from(c - b'0')) % u128::from(modulus)) as u64;
+        }
+        Ok((neg, rem))
+    }
+
+    /// A trait for `{StaticModInt, DynamicModInt, ModIntBase}::new`.
+    pub trait RemEuclidU32 {
+        /// Calculates `self` _mod_ `modulus` losslessly.
+        fn rem_euclid_u32(self, modulus: u32) -> u32;
+    }
+
+    macro_rules! impl_rem_euclid_u32_for_small_signed {
+        ($($ty:tt),*) => {
+            $(
+                impl RemEuclidU32 for $ty {
+                    #[inline]
+                    fn rem_euclid_u32(self, modulus: u32) -> u32 {
+                        (self as i64).rem_euclid(i64::from(modulus)) as _
+                    }
+                }
+            )*
+        }
+    }
+
+    impl_rem_euclid_u32_for_small_signed!(i8, i16, i32, i64, isize);
+
+    impl RemEuclidU32 for i128 {
+        #[inline]
+        fn rem_euclid_u32(self, modulus: u32) -> u32 {
+            self.rem_euclid(i128::from(modulus)) as _
+        }
+    }
+
+    macro_rules! impl_rem_euclid_u32_for_small_unsigned {
+        ($($ty:tt),*) => {
+            $(
+                impl RemEuclidU32 for $ty {
+                    #[inline]
+                    fn rem_euclid_u32(self, modulus: u32) -> u32 {
+                        self as u32 % modulus
+                    }
+                }
+            )*
+        }
+    }
+
+    macro_rules! impl_rem_euclid_u32_for_large_unsigned {
+        ($($ty:tt),*) => {
+            $(
+                impl RemEuclidU32 for $ty {
+                    #[inline]
+                    fn rem_euclid_u32(self, modulus: u32) -> u32 {
+                        (self % (modulus as $ty)) as _
+                    }
+                }
+            )*
+        }
+    }
+
+    impl_rem_euclid_u32_for_small_unsigned!(u8, u16, u32);
+    impl_rem_euclid_u32_for_large_unsigned!(u64, u128);
+
+    #[cfg(target_pointer_width = "32")]
+    impl_rem_euclid_u32_for_small_unsigned!(usize);
+
+    #[cfg(target_pointer_width = "64")]
+    impl_rem_euclid_u32_for_large_unsigned!(usize);
+
+    /// A trait for `{StaticModInt64, DynamicModInt64, ModIntBase64}::new`.
+    pub trait RemEuclidU64 {
+        /// Calculates `self` _mod_ `modulus` losslessly.
+        fn rem_euclid_u64(self, modulus: u64) -> u64;
+    }
+
+    macro_rules! impl_rem_euclid_u64_for_small_signed {
+        ($($ty:tt),*) => {
+            $(
+                impl RemEuclidU64 for $ty {
+                    #[inline]
+                    fn rem_euclid_u64(self, modulus: u64) -> u64 {
+                        (self as i128).rem_euclid(i128::from(modulus)) as _
+                    }
+                }
+            )*
+        }
+    }
+
+    impl_rem_euclid_u64_for_small_signed!(i8, i16, i32, i64, isize, i128);
+
+    macro_rules! impl_rem_euclid_u64_for_small_unsigned {
+        ($($ty:tt),*) => {
+            $(
+                impl RemEuclidU64 for $ty {
+                    #[inline]
+                    fn rem_euclid_u64(self, modulus: u64) -> u64 {
+                        self as u64 % modulus
+                    }
+                }
+            )*
+        }
+    }
+
+    impl_rem_euclid_u64_for_small_unsigned!(u8, u16, u32, u64, usize);
+
+    impl RemEuclidU64 for u128 {
+        #[inline]
+        fn rem_euclid_u64(self, modulus: u64) -> u64 {
+            (self % u128::from(modulus)) as _
+        }
+    }
+
+    trait InternalImplementations: ModIntBase {
+        #[inline]
+        fn inv_for_non_prime_modulus(this: Self) -> Self {
+            let (gcd, x) = internal_math::inv_gcd(this.val().into(), Self::modulus().into());
+            if gcd != 1 {
+                panic!("the multiplicative inverse does not exist");
+            }
+            Self::new(x)
+        }
+
+        #[inline]
+        fn default_impl() -> Self {
+            Self::raw(0)
+        }
+
+        #[inline]
+        fn from_str_impl(s: &str) -> Result<Self, ParseModIntError> {
+            Self::checked_new(s)
+        }
+
+        #[inline]
+        fn hash_impl(this: &Self, state: &mut impl Hasher) {
+            this.val().hash(state)
+        }
+
+        #[inline]
+        fn display_impl(this: &Self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&this.val(), f)
+        }
+
+        #[inline]
+        fn debug_impl(this: &Self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(&this.val(), f)
+        }
+
+        #[inline]
+        fn neg_impl(this: Self) -> Self {
+            Self::sub_impl(Self::raw(0), this)
+        }
+
+        #[inline]
+        fn add_impl(lhs: Self, rhs: Self) -> Self {
+            let modulus = Self::modulus();
+            let mut val = lhs.val() + rhs.val();
+            if val >= modulus {
+                val -= modulus;
+            }
+            Self::raw(val)
+        }
+
+        #[inline]
+        fn sub_impl(lhs: Self, rhs: Self) -> Self {
+            let modulus = Self::modulus();
+            let mut val = lhs.val().wrapping_sub(rhs.val());
+            if val >= modulus {
+                val = val.wrapping_add(modulus)
+            }
+            Self::raw(val)
+        }
+
+        fn mul_impl(lhs: Self, rhs: Self) -> Self;
+
+        #[inline]
+        fn div_impl(lhs: Self, rhs: Self) -> Self {
+            Self::mul_impl(lhs, rhs.inv())
+        }
+    }
+
+    impl<M: Modulus> InternalImplementations for StaticModInt<M> {
+        #[inline]
+        fn mul_impl(lhs: Self, rhs: Self) -> Self {
+            Self::raw((u64::from(lhs.val()) * u64::from(rhs.val()) % u64::from(M::VALUE)) as u32)
+        }
     }
 
-    macro_rules! impl_rem_euclid_u32_for_small_signed {
-        ($($ty:tt),*) => {
-            $(
-                impl RemEuclidU32 for $ty {
-                    #[inline]
-                    fn rem_euclid_u32(self, modulus: u32) -> u32 {
-                        (self as i64).rem_euclid(i64::from(modulus)) as _
-                    }
-                }
-            )*
+    impl<M: Modulus> InternalImplementations for MontgomeryModInt<M> {
+        #[inline]
+        fn neg_impl(this: Self) -> Self {
+            Self::sub_impl(Self::from_mont(0), this)
         }
-    }
 
-    impl_rem_euclid_u32_for_small_signed!(i8, i16, i32, i64, isize);
+        #[inline]
+        fn add_impl(lhs: Self, rhs: Self) -> Self {
+            // `M::VALUE` can be greater than `2^31`
+            let (mut mont, overflowed) = lhs.mont.overflowing_add(rhs.mont);
+            if overflowed || mont >= M::VALUE {
+                mont = mont.wrapping_sub(M::VALUE);
+            }
+            Self::from_mont(mont)
+        }
 
-    impl RemEuclidU32 for i128 {
         #[inline]
-        fn rem_euclid_u32(self, modulus: u32) -> u32 {
-            self.rem_euclid(i128::from(modulus)) as _
+        fn sub_impl(lhs: Self, rhs: Self) -> Self {
+            let (mut mont, overflowed) = lhs.mont.overflowing_sub(rhs.mont);
+            if overflowed {
+                mont = mont.wrapping_add(M::VALUE)
+            }
+            Self::from_mont(mont)
         }
-    }
 
-    macro_rules! impl_rem_euclid_u32_for_small_unsigned {
-        ($($ty:tt),*) => {
-            $(
-                impl RemEuclidU32 for $ty {
-                    #[inline]
-                    fn rem_euclid_u32(self, modulus: u32) -> u32 {
-                        self as u32 % modulus
-                    }
-                }
-            )*
+        #[inline]
+        fn mul_impl(lhs: Self, rhs: Self) -> Self {
+            Self::from_mont(Self::reduce(u64::from(lhs.mont) * u64::from(rhs.mont)))
         }
     }
 
-    macro_rules! impl_rem_euclid_u32_for_large_unsigned {
-        ($($ty:tt),*) => {
-            $(
-                impl RemEuclidU32 for $ty {
-                    #[inline]
-                    fn rem_euclid_u32(self, modulus: u32) -> u32 {
-                        (self % (modulus as $ty)) as _
-                    }
-                }
-            )*
+    impl<I: Id> InternalImplementations for DynamicModInt<I> {
+        #[inline]
+        fn mul_impl(lhs: Self, rhs: Self) -> Self {
+            Self::raw(I::companion_barrett().mul(lhs.val, rhs.val))
         }
     }
 
-    impl_rem_euclid_u32_for_small_unsigned!(u8, u16, u32);
-    impl_rem_euclid_u32_for_large_unsigned!(u64, u128);
-
-    #[cfg(target_pointer_width = "32")]
-    impl_rem_euclid_u32_for_small_unsigned!(usize);
-
-    #[cfg(target_pointer_width = "64")]
-    impl_rem_euclid_u32_for_large_unsigned!(usize);
-
-    trait InternalImplementations: ModIntBase {
+    trait InternalImplementations64: ModIntBase64 {
         #[inline]
         fn inv_for_non_prime_modulus(this: Self) -> Self {
-            let (gcd, x) = internal_math::inv_gcd(this.val().into(), Self::modulus().into());
+            let (gcd, x) = internal_math::inv_gcd_u64(this.val(), Self::modulus());
             if gcd != 1 {
                 panic!("the multiplicative inverse does not exist");
             }
-            Self::new(x)
+            Self::raw(x)
         }
 
         #[inline]
@@ -772,10 +2125,8 @@ mod modint {
         }
 
         #[inline]
-        fn from_str_impl(s: &str) -> Result<Self, Infallible> {
-            Ok(s.parse::<i64>()
-                .map(Self::new)
-                .unwrap_or_else(|_| todo!("parsing as an arbitrary precision integer?")))
+        fn from_str_impl(s: &str) -> Result<Self, ParseModIntError> {
+            Self::checked_new(s)
         }
 
         #[inline]
@@ -801,9 +2152,9 @@ mod modint {
         #[inline]
         fn add_impl(lhs: Self, rhs: Self) -> Self {
             let modulus = Self::modulus();
-            let mut val = lhs.val() + rhs.val();
-            if val >= modulus {
-                val -= modulus;
+            let (mut val, overflowed) = lhs.val().overflowing_add(rhs.val());
+            if overflowed || val >= modulus {
+                val = val.wrapping_sub(modulus);
             }
             Self::raw(val)
         }
@@ -811,8 +2162,8 @@ mod modint {
         #[inline]
         fn sub_impl(lhs: Self, rhs: Self) -> Self {
             let modulus = Self::modulus();
-            let mut val = lhs.val().wrapping_sub(rhs.val());
-            if val >= modulus {
+            let (mut val, borrowed) = lhs.val().overflowing_sub(rhs.val());
+            if borrowed {
                 val = val.wrapping_add(modulus)
             }
             Self::raw(val)
@@ -826,14 +2177,19 @@ mod modint {
         }
     }
 
-    impl<M: Modulus> InternalImplementations for StaticModInt<M> {
+    impl<M: Modulus64> InternalImplementations64 for StaticModInt64<M> {
         #[inline]
         fn mul_impl(lhs: Self, rhs: Self) -> Self {
-            Self::raw((u64::from(lhs.val()) * u64::from(rhs.val()) % u64::from(M::VALUE)) as u32)
+            Self::raw(internal_math::mul_mod_u64(
+                lhs.val(),
+                rhs.val(),
+                M::VALUE,
+                Self::IM,
+            ))
         }
     }
 
-    impl<I: Id> InternalImplementations for DynamicModInt<I> {
+    impl<I: Id64> InternalImplementations64 for DynamicModInt64<I> {
         #[inline]
         fn mul_impl(lhs: Self, rhs: Self) -> Self {
             Self::raw(I::companion_barrett().mul(lhs.val, rhs.val))
@@ -842,7 +2198,7 @@ mod modint {
 
     macro_rules! impl_basic_traits {
         () => {};
-        (impl <$generic_param:ident : $generic_param_bound:tt> _ for $self:ty; $($rest:tt)*) => {
+        (impl <$generic_param:ident : $generic_param_bound:tt, $from_param:ident : $from_param_bound:tt> _ for $self:ty; $($rest:tt)*) => {
             impl <$generic_param: $generic_param_bound> Default for $self {
                 #[inline]
                 fn default() -> Self {
@@ -851,17 +2207,17 @@ mod modint {
             }
 
             impl <$generic_param: $generic_param_bound> FromStr for $self {
-                type Err = Infallible;
+                type Err = ParseModIntError;
 
                 #[inline]
-                fn from_str(s: &str) -> Result<Self, Infallible> {
+                fn from_str(s: &str) -> Result<Self, ParseModIntError> {
                     Self::from_str_impl(s)
                 }
             }
 
-            impl<$generic_param: $generic_param_bound, V: RemEuclidU32> From<V> for $self {
+            impl<$generic_param: $generic_param_bound, $from_param: $from_param_bound> From<$from_param> for $self {
                 #[inline]
-                fn from(from: V) -> Self {
+                fn from(from: $from_param) -> Self {
                     Self::new(from)
                 }
             }
@@ -911,8 +2267,74 @@ mod modint {
     }
 
     impl_basic_traits! {
-        impl <M: Modulus> _ for StaticModInt<M> ;
-        impl <I: Id     > _ for DynamicModInt<I>;
+        impl <M: Modulus  , V: RemEuclidU32> _ for StaticModInt<M>    ;
+        impl <M: Modulus  , V: RemEuclidU32> _ for MontgomeryModInt<M>;
+        impl <I: Id       , V: RemEuclidU32> _ for DynamicModInt<I>   ;
+        impl <M: Modulus64, V: RemEuclidU64> _ for StaticModInt64<M>  ;
+        impl <I: Id64     , V: RemEuclidU64> _ for DynamicModInt64<I> ;
+    }
+
+    // Modints are serialized as their `val`s. Deserialized integers are reduced with `new`, so any integer (including
+    // negative ones) is accepted and anything else is rejected.
+    #[cfg(feature = "serde")]
+    macro_rules! impl_serde {
+        () => {};
+        (impl <$generic_param:ident : $generic_param_bound:tt> _ for $self:ty { $serialize:ident, $deserialize:ident } $($rest:tt)*) => {
+            impl<$generic_param: $generic_param_bound> serde::Serialize for $self {
+                #[inline]
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.$serialize(self.val())
+                }
+            }
+
+            impl<'de, $generic_param: $generic_param_bound> serde::Deserialize<'de> for $self {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    struct Visitor<$generic_param>(PhantomData<fn() -> $generic_param>);
+
+                    impl<'de, $generic_param: $generic_param_bound> serde::de::Visitor<'de> for Visitor<$generic_param> {
+                        type Value = $self;
+
+                        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                            f.write_str("an integer")
+                        }
+
+                        #[inline]
+                        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<$self, E> {
+                            Ok(<$self>::new(v))
+                        }
+
+                        #[inline]
+                        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<$self, E> {
+                            Ok(<$self>::new(v))
+                        }
+
+                        #[inline]
+                        fn visit_u128<E: serde::de::Error>(self, v: u128) -> Result<$self, E> {
+                            Ok(<$self>::new(v))
+                        }
+
+                        #[inline]
+                        fn visit_i128<E: serde::de::Error>(self, v: i128) -> Result<$self, E> {
+                            Ok(<$self>::new(v))
+                        }
+                    }
+
+                    // non-self-describing formats need the same width as `serialize`
+                    deserializer.$deserialize(Visitor(PhantomData))
+                }
+            }
+
+            impl_serde!($($rest)*);
+        };
+    }
+
+    #[cfg(feature = "serde")]
+    impl_serde! {
+        impl <M: Modulus  > _ for StaticModInt<M>     { serialize_u32, deserialize_u32 }
+        impl <M: Modulus  > _ for MontgomeryModInt<M> { serialize_u32, deserialize_u32 }
+        impl <I: Id       > _ for DynamicModInt<I>    { serialize_u32, deserialize_u32 }
+        impl <M: Modulus64> _ for StaticModInt64<M>   { serialize_u64, deserialize_u64 }
+        impl <I: Id64     > _ for DynamicModInt64<I>  { serialize_u64, deserialize_u64 }
     }
 
     macro_rules! impl_bin_ops {
@@ -967,99 +2389,504 @@ mod modint {
                     *self = *self + apply($rhs_body, rhs);
                 }
             }
-
-            impl <$($generic_param: $generic_param_bound),*> SubAssign<$rhs_ty> for $lhs_ty {
-                #[inline]
-                fn sub_assign(&mut self, rhs: $rhs_ty) {
-                    *self = *self - apply($rhs_body, rhs);
-                }
+
+            impl <$($generic_param: $generic_param_bound),*> SubAssign<$rhs_ty> for $lhs_ty {
+                #[inline]
+                fn sub_assign(&mut self, rhs: $rhs_ty) {
+                    *self = *self - apply($rhs_body, rhs);
+                }
+            }
+
+            impl <$($generic_param: $generic_param_bound),*> MulAssign<$rhs_ty> for $lhs_ty {
+                #[inline]
+                fn mul_assign(&mut self, rhs: $rhs_ty) {
+                    *self = *self * apply($rhs_body, rhs);
+                }
+            }
+
+            impl <$($generic_param: $generic_param_bound),*> DivAssign<$rhs_ty> for $lhs_ty {
+                #[inline]
+                fn div_assign(&mut self, rhs: $rhs_ty) {
+                    *self = *self / apply($rhs_body, rhs);
+                }
+            }
+
+            impl_assign_ops!($($rest)*);
+        };
+    }
+
+    #[inline]
+    fn apply<F: FnOnce(X) -> O, X, O>(f: F, x: X) -> O {
+        f(x)
+    }
+
+    impl_bin_ops! {
+        for<M: Modulus> <StaticModInt<M>     > ~ <StaticModInt<M>     > -> StaticModInt<M>  { { |x| x  } ~ { |x| x  } }
+        for<M: Modulus> <StaticModInt<M>     > ~ <&'_ StaticModInt<M> > -> StaticModInt<M>  { { |x| x  } ~ { |&x| x } }
+        for<M: Modulus> <&'_ StaticModInt<M> > ~ <StaticModInt<M>     > -> StaticModInt<M>  { { |&x| x } ~ { |x| x  } }
+        for<M: Modulus> <&'_ StaticModInt<M> > ~ <&'_ StaticModInt<M> > -> StaticModInt<M>  { { |&x| x } ~ { |&x| x } }
+        for<M: Modulus> <MontgomeryModInt<M>     > ~ <MontgomeryModInt<M>     > -> MontgomeryModInt<M> { { |x| x  } ~ { |x| x  } }
+        for<M: Modulus> <MontgomeryModInt<M>     > ~ <&'_ MontgomeryModInt<M> > -> MontgomeryModInt<M> { { |x| x  } ~ { |&x| x } }
+        for<M: Modulus> <&'_ MontgomeryModInt<M> > ~ <MontgomeryModInt<M>     > -> MontgomeryModInt<M> { { |&x| x } ~ { |x| x  } }
+        for<M: Modulus> <&'_ MontgomeryModInt<M> > ~ <&'_ MontgomeryModInt<M> > -> MontgomeryModInt<M> { { |&x| x } ~ { |&x| x } }
+        for<I: Id     > <DynamicModInt<I>    > ~ <DynamicModInt<I>    > -> DynamicModInt<I> { { |x| x  } ~ { |x| x  } }
+        for<I: Id     > <DynamicModInt<I>    > ~ <&'_ DynamicModInt<I>> -> DynamicModInt<I> { { |x| x  } ~ { |&x| x } }
+        for<I: Id     > <&'_ DynamicModInt<I>> ~ <DynamicModInt<I>    > -> DynamicModInt<I> { { |&x| x } ~ { |x| x  } }
+        for<I: Id     > <&'_ DynamicModInt<I>> ~ <&'_ DynamicModInt<I>> -> DynamicModInt<I> { { |&x| x } ~ { |&x| x } }
+
+        for<M: Modulus, T: RemEuclidU32> <StaticModInt<M>     > ~ <T> -> StaticModInt<M>  { { |x| x  } ~ { StaticModInt::<M>::new } }
+        for<M: Modulus, T: RemEuclidU32> <MontgomeryModInt<M> > ~ <T> -> MontgomeryModInt<M> { { |x| x  } ~ { MontgomeryModInt::<M>::new } }
+        for<I: Id     , T: RemEuclidU32> <DynamicModInt<I>    > ~ <T> -> DynamicModInt<I> { { |x| x  } ~ { DynamicModInt::<I>::new } }
+
+        for<M: Modulus64> <StaticModInt64<M>     > ~ <StaticModInt64<M>     > -> StaticModInt64<M>  { { |x| x  } ~ { |x| x  } }
+        for<M: Modulus64> <StaticModInt64<M>     > ~ <&'_ StaticModInt64<M> > -> StaticModInt64<M>  { { |x| x  } ~ { |&x| x } }
+        for<M: Modulus64> <&'_ StaticModInt64<M> > ~ <StaticModInt64<M>     > -> StaticModInt64<M>  { { |&x| x } ~ { |x| x  } }
+        for<M: Modulus64> <&'_ StaticModInt64<M> > ~ <&'_ StaticModInt64<M> > -> StaticModInt64<M>  { { |&x| x } ~ { |&x| x } }
+        for<I: Id64     > <DynamicModInt64<I>    > ~ <DynamicModInt64<I>    > -> DynamicModInt64<I> { { |x| x  } ~ { |x| x  } }
+        for<I: Id64     > <DynamicModInt64<I>    > ~ <&'_ DynamicModInt64<I>> -> DynamicModInt64<I> { { |x| x  } ~ { |&x| x } }
+        for<I: Id64     > <&'_ DynamicModInt64<I>> ~ <DynamicModInt64<I>    > -> DynamicModInt64<I> { { |&x| x } ~ { |x| x  } }
+        for<I: Id64     > <&'_ DynamicModInt64<I>> ~ <&'_ DynamicModInt64<I>> -> DynamicModInt64<I> { { |&x| x } ~ { |&x| x } }
+
+        for<M: Modulus64, T: RemEuclidU64> <StaticModInt64<M> > ~ <T> -> StaticModInt64<M>  { { |x| x  } ~ { StaticModInt64::<M>::new } }
+        for<I: Id64     , T: RemEuclidU64> <DynamicModInt64<I>> ~ <T> -> DynamicModInt64<I> { { |x| x  } ~ { DynamicModInt64::<I>::new } }
+    }
+
+    impl_assign_ops! {
+        for<M: Modulus> <StaticModInt<M> > ~= <StaticModInt<M>     > { _ ~= { |x| x  } }
+        for<M: Modulus> <StaticModInt<M> > ~= <&'_ StaticModInt<M> > { _ ~= { |&x| x } }
+        for<M: Modulus> <MontgomeryModInt<M>> ~= <MontgomeryModInt<M>     > { _ ~= { |x| x  } }
+        for<M: Modulus> <MontgomeryModInt<M>> ~= <&'_ MontgomeryModInt<M> > { _ ~= { |&x| x } }
+        for<I: Id     > <DynamicModInt<I>> ~= <DynamicModInt<I>    > { _ ~= { |x| x  } }
+        for<I: Id     > <DynamicModInt<I>> ~= <&'_ DynamicModInt<I>> { _ ~= { |&x| x } }
+
+        for<M: Modulus, T: RemEuclidU32> <StaticModInt<M> > ~= <T> { _ ~= { StaticModInt::<M>::new } }
+        for<M: Modulus, T: RemEuclidU32> <MontgomeryModInt<M>> ~= <T> { _ ~= { MontgomeryModInt::<M>::new } }
+        for<I: Id,      T: RemEuclidU32> <DynamicModInt<I>> ~= <T> { _ ~= { DynamicModInt::<I>::new } }
+
+        for<M: Modulus64> <StaticModInt64<M> > ~= <StaticModInt64<M>     > { _ ~= { |x| x  } }
+        for<M: Modulus64> <StaticModInt64<M> > ~= <&'_ StaticModInt64<M> > { _ ~= { |&x| x } }
+        for<I: Id64     > <DynamicModInt64<I>> ~= <DynamicModInt64<I>    > { _ ~= { |x| x  } }
+        for<I: Id64     > <DynamicModInt64<I>> ~= <&'_ DynamicModInt64<I>> { _ ~= { |&x| x } }
+
+        for<M: Modulus64, T: RemEuclidU64> <StaticModInt64<M> > ~= <T> { _ ~= { StaticModInt64::<M>::new } }
+        for<I: Id64,      T: RemEuclidU64> <DynamicModInt64<I>> ~= <T> { _ ~= { DynamicModInt64::<I>::new } }
+    }
+
+    macro_rules! impl_folding {
+        () => {};
+        (impl<$generic_param:ident : $generic_param_bound:tt> $trait:ident<_> for $self:ty { fn $method:ident(_) -> _ { _($unit:expr, $op:expr) } } $($rest:tt)*) => {
+            impl<$generic_param: $generic_param_bound> $trait<Self> for $self {
+                #[inline]
+                fn $method<S>(iter: S) -> Self
+                where
+                    S: Iterator<Item = Self>,
+                {
+                    iter.fold($unit, $op)
+                }
+            }
+
+            impl<'a, $generic_param: $generic_param_bound> $trait<&'a Self> for $self {
+                #[inline]
+                fn $method<S>(iter: S) -> Self
+                where
+                    S: Iterator<Item = &'a Self>,
+                {
+                    iter.fold($unit, $op)
+                }
+            }
+
+            impl_folding!($($rest)*);
+        };
+    }
+
+    impl_folding! {
+        impl<M: Modulus> Sum<_>     for StaticModInt<M>  { fn sum(_)     -> _ { _(Self::raw(0), Add::add) } }
+        impl<M: Modulus> Product<_> for StaticModInt<M>  { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
+        impl<M: Modulus> Sum<_>     for MontgomeryModInt<M> { fn sum(_)     -> _ { _(Self::from_mont(0), Add::add) } }
+        impl<M: Modulus> Product<_> for MontgomeryModInt<M> { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
+        impl<I: Id     > Sum<_>     for DynamicModInt<I> { fn sum(_)     -> _ { _(Self::raw(0), Add::add) } }
+        impl<I: Id     > Product<_> for DynamicModInt<I> { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
+        impl<M: Modulus64> Sum<_>     for StaticModInt64<M>  { fn sum(_)     -> _ { _(Self::raw(0), Add::add) } }
+        impl<M: Modulus64> Product<_> for StaticModInt64<M>  { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
+        impl<I: Id64     > Sum<_>     for DynamicModInt64<I> { fn sum(_)     -> _ { _(Self::raw(0), Add::add) } }
+        impl<I: Id64     > Product<_> for DynamicModInt64<I> { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
+    }
+
+    /// Table of factorials and their inverses, for binomial coefficients and their friends.
+    ///
+    /// The table grows on demand. Each growth costs _O(n)_ operations and only one [`inv`].
+    ///
+    /// # Constraints
+    ///
+    /// - The modulus is a prime number larger than any argument that is passed to the methods.
+    /// - For [`DynamicModInt`], the modulus must not change while the table is alive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{Binomial, ModInt998244353 as Mint};
+    ///
+    /// let mut binomial = Binomial::<Mint>::new(10);
+    ///
+    /// assert_eq!(Mint::new(10), binomial.binom(5, 2));
+    /// assert_eq!(Mint::new(20), binomial.perm(5, 2));
+    /// assert_eq!(Mint::new(30), binomial.multinomial(&[1, 2, 2]));
+    /// assert_eq!(Mint::new(42), binomial.catalan(5));
+    /// assert_eq!(Mint::new(3).inv(), binomial.inv(3));
+    ///
+    /// // grows on demand
+    /// assert_eq!(Mint::new(4950), binomial.binom(100, 2));
+    /// ```
+    ///
+    /// [`inv`]: ./trait.ModIntBase.html#tymethod.inv
+    /// [`DynamicModInt`]: ./struct.DynamicModInt.html
+    #[derive(Clone, Debug)]
+    pub struct Binomial<Z> {
+        fact: Vec<Z>,
+        inv_fact: Vec<Z>,
+    }
+
+    impl<Z: ModIntBase> Binomial<Z> {
+        /// Creates a new `Binomial` that holds `0!`, `1!`, …, `n!` and their inverses.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(n)_
+        pub fn new(n: usize) -> Self {
+            let mut this = Self {
+                fact: vec![Z::raw(1)],
+                inv_fact: vec![Z::raw(1)],
+            };
+            this.reserve(n);
+            this
+        }
+
+        /// Returns _n!_.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(1)_ amortized
+        #[inline]
+        pub fn fact(&mut self, n: usize) -> Z {
+            self.reserve(n);
+            self.fact[n]
+        }
+
+        /// Returns _(n!)⁻¹_.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(1)_ amortized
+        #[inline]
+        pub fn inv_fact(&mut self, n: usize) -> Z {
+            self.reserve(n);
+            self.inv_fact[n]
+        }
+
+        /// Returns _n⁻¹_.
+        ///
+        /// # Constraints
+        ///
+        /// - `1 <= n`
+        ///
+        /// # Panics
+        ///
+        /// Panics if `n` is `0`.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(1)_ amortized
+        #[inline]
+        pub fn inv(&mut self, n: usize) -> Z {
+            assert!(n > 0, "attempt to divide by zero");
+            self.reserve(n);
+            self.inv_fact[n] * self.fact[n - 1]
+        }
+
+        /// Returns _nCk_, which is `0` if _k > n_.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(1)_ amortized
+        #[inline]
+        pub fn binom(&mut self, n: usize, k: usize) -> Z {
+            if k > n {
+                return Z::raw(0);
+            }
+            self.reserve(n);
+            self.fact[n] * self.inv_fact[k] * self.inv_fact[n - k]
+        }
+
+        /// Returns _nPk = n!/(n - k)!_, which is `0` if _k > n_.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(1)_ amortized
+        #[inline]
+        pub fn perm(&mut self, n: usize, k: usize) -> Z {
+            if k > n {
+                return Z::raw(0);
+            }
+            self.reserve(n);
+            self.fact[n] * self.inv_fact[n - k]
+        }
+
+        /// Returns _(k₀ + k₁ + ⋯)!/(k₀!k₁!⋯)_.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(|ks|)_ amortized
+        pub fn multinomial(&mut self, ks: &[usize]) -> Z {
+            let n = ks.iter().sum();
+            self.reserve(n);
+            ks.iter()
+                .fold(self.fact[n], |acc, &k| acc * self.inv_fact[k])
+        }
+
+        /// Returns the _n_-th Catalan number _(2n)!/(n!(n + 1)!)_.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(1)_ amortized
+        #[inline]
+        pub fn catalan(&mut self, n: usize) -> Z {
+            self.reserve(cmp::max(2 * n, n + 1));
+            self.fact[2 * n] * self.inv_fact[n] * self.inv_fact[n + 1]
+        }
+
+        /// Makes the table hold at least `0!`, `1!`, …, `n!` and their inverses.
+        ///
+        /// The capacity is at least doubled on each growth.
+        ///
+        /// # Complexity
+        ///
+        /// - _O(n)_
+        pub fn reserve(&mut self, n: usize) {
+            let len = self.fact.len();
+            if n < len {
+                return;
+            }
+            let new_len = cmp::max(n + 1, 2 * len);
+            for i in len..new_len {
+                let x = self.fact[i - 1] * Z::new(i);
+                self.fact.push(x);
             }
-
-            impl <$($generic_param: $generic_param_bound),*> MulAssign<$rhs_ty> for $lhs_ty {
-                #[inline]
-                fn mul_assign(&mut self, rhs: $rhs_ty) {
-                    *self = *self * apply($rhs_body, rhs);
-                }
+            self.inv_fact.resize(new_len, Z::raw(0));
+            self.inv_fact[new_len - 1] = self.fact[new_len - 1].inv();
+            for i in (len..new_len - 1).rev() {
+                self.inv_fact[i] = self.inv_fact[i + 1] * Z::new(i + 1);
             }
+        }
+    }
 
-            impl <$($generic_param: $generic_param_bound),*> DivAssign<$rhs_ty> for $lhs_ty {
-                #[inline]
-                fn div_assign(&mut self, rhs: $rhs_ty) {
-                    *self = *self / apply($rhs_body, rhs);
-                }
+    /// Returns the minimum _k ≥ 0_ such that _baseᵏ = target_, or `None` if there is no such _k_.
+    ///
+    /// The modulus does not have to be a prime number, and `base` does not have to be coprime to it.
+    ///
+    /// # Complexity
+    ///
+    /// - _O(√m)_ expected (baby-step giant-step with a `HashMap`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint, ModInt1000000007 as Mint};
+    ///
+    /// assert_eq!(Some(10), modint::discrete_log(Mint::new(2), Mint::new(1024)));
+    /// assert_eq!(Some(0), modint::discrete_log(Mint::new(0), Mint::new(1)));
+    /// assert_eq!(None, modint::discrete_log(Mint::new(1), Mint::new(2)));
+    /// ```
+    #[allow(clippy::many_single_char_names)]
+    pub fn discrete_log<Z: ModIntBase>(base: Z, target: Z) -> Option<u64> {
+        let mut m = u64::from(Z::modulus());
+        let a = u64::from(base.val());
+        let mut b = u64::from(target.val());
+        if m == 1 {
+            return Some(0);
+        }
+
+        // The answer is small while `a^k` is "absorbing" the common factors with `m`.
+        // Since every such factor is at least 2, `a^k` is coprime to `m / gcd(a^k, m)` for k >= 32.
+        const SMALL: u64 = 32;
+        let mut cur = 1;
+        for k in 0..SMALL {
+            if cur == b {
+                return Some(k);
             }
+            cur = cur * a % m;
+        }
 
-            impl_assign_ops!($($rest)*);
-        };
+        // Reduce to `e * a^x = b (mod m)` where `gcd(a, m) = 1`, then the answer is `x + cnt`.
+        let mut e = 1 % m;
+        let mut cnt = 0;
+        loop {
+            let g = gcd(a, m);
+            if g == 1 {
+                break;
+            }
+            if b % g != 0 {
+                return None;
+            }
+            m /= g;
+            b /= g;
+            e = e * (a / g) % m;
+            cnt += 1;
+        }
+        let a = a % m;
+
+        // x = i * n - j (1 <= i <= n, 0 <= j < n)
+        let n = (1..).find(|&n| n * n >= m).unwrap();
+        let mut baby = HashMap::new();
+        let mut cur = b;
+        for j in 0..n {
+            baby.insert(cur, j);
+            cur = cur * a % m;
+        }
+        let an = (0..n).fold(1 % m, |acc, _| acc * a % m);
+        let mut giant = e;
+        for i in 1..=n {
+            giant = giant * an % m;
+            if let Some(&j) = baby.get(&giant) {
+                return Some(i * n - j + cnt);
+            }
+        }
+        None
     }
 
-    #[inline]
-    fn apply<F: FnOnce(X) -> O, X, O>(f: F, x: X) -> O {
-        f(x)
+    /// Replaces each element of `a` with its multiplicative inverse.
+    ///
+    /// Uses the Montgomery's trick, so [`inv`] is called only once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the multiplicative inverses does not exist.
+    ///
+    /// # Complexity
+    ///
+    /// - _O(n)_ multiplications and one [`inv`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint, ModInt1000000007 as Mint};
+    ///
+    /// let mut a = [Mint::new(1), Mint::new(2), Mint::new(3)];
+    /// modint::batch_inv(&mut a);
+    /// assert_eq!([Mint::new(1), Mint::new(2).inv(), Mint::new(3).inv()], a);
+    /// ```
+    ///
+    /// [`inv`]: ./trait.ModIntBase.html#tymethod.inv
+    pub fn batch_inv<Z: ModIntBase>(a: &mut [Z]) {
+        let prefix = prefix_products(a);
+        let mut inv = prefix[a.len()].inv();
+        for (x, &p) in a.iter_mut().zip(&prefix).rev() {
+            let next = inv * *x;
+            *x = inv * p;
+            inv = next;
+        }
     }
 
-    impl_bin_ops! {
-        for<M: Modulus> <StaticModInt<M>     > ~ <StaticModInt<M>     > -> StaticModInt<M>  { { |x| x  } ~ { |x| x  } }
-        for<M: Modulus> <StaticModInt<M>     > ~ <&'_ StaticModInt<M> > -> StaticModInt<M>  { { |x| x  } ~ { |&x| x } }
-        for<M: Modulus> <&'_ StaticModInt<M> > ~ <StaticModInt<M>     > -> StaticModInt<M>  { { |&x| x } ~ { |x| x  } }
-        for<M: Modulus> <&'_ StaticModInt<M> > ~ <&'_ StaticModInt<M> > -> StaticModInt<M>  { { |&x| x } ~ { |&x| x } }
-        for<I: Id     > <DynamicModInt<I>    > ~ <DynamicModInt<I>    > -> DynamicModInt<I> { { |x| x  } ~ { |x| x  } }
-        for<I: Id     > <DynamicModInt<I>    > ~ <&'_ DynamicModInt<I>> -> DynamicModInt<I> { { |x| x  } ~ { |&x| x } }
-        for<I: Id     > <&'_ DynamicModInt<I>> ~ <DynamicModInt<I>    > -> DynamicModInt<I> { { |&x| x } ~ { |x| x  } }
-        for<I: Id     > <&'_ DynamicModInt<I>> ~ <&'_ DynamicModInt<I>> -> DynamicModInt<I> { { |&x| x } ~ { |&x| x } }
-
-        for<M: Modulus, T: RemEuclidU32> <StaticModInt<M>     > ~ <T> -> StaticModInt<M>  { { |x| x  } ~ { StaticModInt::<M>::new } }
-        for<I: Id     , T: RemEuclidU32> <DynamicModInt<I>    > ~ <T> -> DynamicModInt<I> { { |x| x  } ~ { DynamicModInt::<I>::new } }
+    /// Multiplies each `a[i]` by `b[i]`.
+    ///
+    /// # Constraints
+    ///
+    /// - `a.len() == b.len()`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the above constraint is not satisfied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint, ModInt1000000007 as Mint};
+    ///
+    /// let mut a = [Mint::new(1), Mint::new(2)];
+    /// modint::pointwise_mul(&mut a, &[Mint::new(3), Mint::new(4)]);
+    /// assert_eq!([Mint::new(3), Mint::new(8)], a);
+    /// ```
+    pub fn pointwise_mul<Z: ModIntBase>(a: &mut [Z], b: &[Z]) {
+        assert_eq!(a.len(), b.len());
+        for (a, &b) in a.iter_mut().zip(b) {
+            *a *= b;
+        }
     }
 
-    impl_assign_ops! {
-        for<M: Modulus> <StaticModInt<M> > ~= <StaticModInt<M>     > { _ ~= { |x| x  } }
-        for<M: Modulus> <StaticModInt<M> > ~= <&'_ StaticModInt<M> > { _ ~= { |&x| x } }
-        for<I: Id     > <DynamicModInt<I>> ~= <DynamicModInt<I>    > { _ ~= { |x| x  } }
-        for<I: Id     > <DynamicModInt<I>> ~= <&'_ DynamicModInt<I>> { _ ~= { |&x| x } }
-
-        for<M: Modulus, T: RemEuclidU32> <StaticModInt<M> > ~= <T> { _ ~= { StaticModInt::<M>::new } }
-        for<I: Id,      T: RemEuclidU32> <DynamicModInt<I>> ~= <T> { _ ~= { DynamicModInt::<I>::new } }
+    /// Multiplies each element of `a` by `c`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint, ModInt1000000007 as Mint};
+    ///
+    /// let mut a = [Mint::new(1), Mint::new(2)];
+    /// modint::scale(&mut a, Mint::new(3));
+    /// assert_eq!([Mint::new(3), Mint::new(6)], a);
+    /// ```
+    pub fn scale<Z: ModIntBase>(a: &mut [Z], c: Z) {
+        for a in a {
+            *a *= c;
+        }
     }
 
-    macro_rules! impl_folding {
-        () => {};
-        (impl<$generic_param:ident : $generic_param_bound:tt> $trait:ident<_> for $self:ty { fn $method:ident(_) -> _ { _($unit:expr, $op:expr) } } $($rest:tt)*) => {
-            impl<$generic_param: $generic_param_bound> $trait<Self> for $self {
-                #[inline]
-                fn $method<S>(iter: S) -> Self
-                where
-                    S: Iterator<Item = Self>,
-                {
-                    iter.fold($unit, $op)
-                }
-            }
-
-            impl<'a, $generic_param: $generic_param_bound> $trait<&'a Self> for $self {
-                #[inline]
-                fn $method<S>(iter: S) -> Self
-                where
-                    S: Iterator<Item = &'a Self>,
-                {
-                    iter.fold($unit, $op)
-                }
-            }
-
-            impl_folding!($($rest)*);
-        };
+    /// Returns the prefix products of `a`.
+    ///
+    /// The length of the returned `Vec` is `a.len() + 1`, and its `i`-th element is `a[0] * a[1] * … * a[i - 1]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ac_library_rs::{modint, ModInt1000000007 as Mint};
+    ///
+    /// let a = [Mint::new(2), Mint::new(3), Mint::new(4)];
+    /// assert_eq!(
+    ///     vec![Mint::new(1), Mint::new(2), Mint::new(6), Mint::new(24)],
+    ///     modint::prefix_products(&a),
+    /// );
+    /// ```
+    pub fn prefix_products<Z: ModIntBase>(a: &[Z]) -> Vec<Z> {
+        let mut ret = Vec::with_capacity(a.len() + 1);
+        ret.push(Z::raw(1));
+        ret.extend(a.iter().scan(Z::raw(1), |acc, &x| {
+            *acc *= x;
+            Some(*acc)
+        }));
+        ret
     }
 
-    impl_folding! {
-        impl<M: Modulus> Sum<_>     for StaticModInt<M>  { fn sum(_)     -> _ { _(Self::raw(0), Add::add) } }
-        impl<M: Modulus> Product<_> for StaticModInt<M>  { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
-        impl<I: Id     > Sum<_>     for DynamicModInt<I> { fn sum(_)     -> _ { _(Self::raw(0), Add::add) } }
-        impl<I: Id     > Product<_> for DynamicModInt<I> { fn product(_) -> _ { _(Self::raw(1), Mul::mul) } }
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
     }
 
     #[cfg(test)]
     mod tests {
         use super::ModInt1000000007;
 
+        macro_rules! modulus {
+            ($($name:ident($value:expr, $is_prime:expr)),*) => {
+                $(
+                    #[derive(Copy, Clone, Eq, PartialEq)]
+                    enum $name {}
+
+                    impl super::Modulus for $name {
+                        const VALUE: u32 = $value;
+                        const HINT_VALUE_IS_PRIME: bool = $is_prime;
+
+                        fn butterfly_cache() -> &'static ::std::thread::LocalKey<::std::cell::RefCell<::std::option::Option<super::ButterflyCache<Self>>>> {
+                            thread_local! {
+                                static BUTTERFLY_CACHE: ::std::cell::RefCell<::std::option::Option<super::ButterflyCache<$name>>> = ::std::default::Default::default();
+                            }
+                            &BUTTERFLY_CACHE
+                        }
+                    }
+                )*
+            };
+        }
+
         #[test]
         fn static_modint_new() {
             assert_eq!(0, ModInt1000000007::new(0u32).val);
@@ -1165,5 +2992,608 @@ mod modint {
             c /= b;
             assert_eq!(expected, c);
         }
+
+        #[test]
+        fn montgomery_modint_matches_static_modint() {
+            use super::{Mod1000000007, MontgomeryModInt};
+
+            type Z = MontgomeryModInt<Mod1000000007>;
+
+            let values = [0, 1, 2, 3, 42, 100_000, 999_999_937, 1_000_000_006];
+            for &a in &values {
+                assert_eq!(a, Z::new(a).val());
+                assert_eq!(ModInt1000000007::new(a), Z::new(a).into());
+                assert_eq!(-ModInt1000000007::new(a), (-Z::new(a)).into());
+                for &b in &values {
+                    let (x, y) = (ModInt1000000007::new(a), ModInt1000000007::new(b));
+                    let (z, w) = (Z::new(a), Z::new(b));
+                    assert_eq!(x + y, (z + w).into());
+                    assert_eq!(x - y, (z - w).into());
+                    assert_eq!(x * y, (z * w).into());
+                    if b != 0 {
+                        assert_eq!(x / y, (z / w).into());
+                    }
+                }
+            }
+            assert_eq!(
+                ModInt1000000007::new(-120),
+                [-1, 2, -3, 4, -5]
+                    .iter()
+                    .map(|&x| Z::new(x))
+                    .product::<Z>()
+                    .into(),
+            );
+        }
+
+        #[test]
+        fn static_modint64_ops() {
+            use super::{Mod2305843009213693951, ModInt2305843009213693951 as Z, Modulus64};
+
+            const M: u128 = Mod2305843009213693951::VALUE as u128;
+
+            let values = [0, 1, 2, 3, 1 << 40, (1 << 60) + 12345, (1 << 61) - 2];
+            for &a in &values {
+                assert_eq!(a, Z::new(a).val());
+                assert_eq!((M - u128::from(a)) % M, u128::from((-Z::new(a)).val()));
+                for &b in &values {
+                    let (x, y) = (u128::from(a), u128::from(b));
+                    let (z, w) = (Z::new(a), Z::new(b));
+                    assert_eq!((x + y) % M, (z + w).val().into());
+                    assert_eq!((M + x - y) % M, (z - w).val().into());
+                    assert_eq!(x * y % M, (z * w).val().into());
+                    if b != 0 {
+                        assert_eq!(z, z / w * w);
+                    }
+                }
+            }
+            assert_eq!(Z::new(-1), Z::new((1u64 << 61) - 2));
+            assert_eq!(
+                Z::new(-120),
+                [-1, 2, -3, 4, -5].iter().map(|&x| Z::new(x)).product()
+            );
+            assert_eq!(
+                Z::new(-3),
+                [-1, 2, -3, 4, -5].iter().map(|&x| Z::new(x)).sum()
+            );
+            assert_eq!(Ok(Z::new(-7)), "-7".parse());
+            // out of the range of `i128`
+            assert_eq!(
+                Ok(Z::new(10).pow(40) + 1),
+                "10000000000000000000000000000000000000001".parse()
+            );
+            assert_eq!(
+                Ok(-Z::new(10).pow(40)),
+                "-10000000000000000000000000000000000000000".parse()
+            );
+        }
+
+        #[test]
+        fn dynamic_modint64_ops() {
+            use super::ModInt64 as Z;
+
+            // larger than 2^63, not a prime
+            const M: u64 = (1 << 63) + 1;
+
+            Z::set_modulus(M);
+            assert_eq!(M, Z::modulus());
+            assert_eq!(M - 1, (Z::new(M - 2) + Z::new(M - 1) + 2).val());
+            assert_eq!(1, (Z::new(M - 1) * Z::new(M - 1)).val());
+            assert_eq!(2, (Z::new(1) - Z::new(M - 1)).val());
+            assert_eq!(1, (Z::new(2) * Z::new(2).inv()).val());
+            assert_eq!(Z::new(2).pow(63), Z::new(-1));
+        }
+
+        #[test]
+        #[should_panic]
+        fn dynamic_modint64_inv_of_non_coprime() {
+            use super::{DynamicModInt64, Id64};
+
+            #[derive(Copy, Clone, Eq, PartialEq)]
+            enum Id {}
+
+            impl Id64 for Id {
+                fn companion_barrett() -> &'static super::Barrett64 {
+                    static BARRETT: super::Barrett64 = super::Barrett64::new(1 << 62);
+                    &BARRETT
+                }
+            }
+
+            DynamicModInt64::<Id>::new(2).inv();
+        }
+
+        #[test]
+        fn binomial() {
+            use super::{Binomial, DynamicModInt, ModInt998244353};
+
+            fn binom_naive(n: u64, k: u64) -> u64 {
+                (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+            }
+
+            let mut binomial = Binomial::<ModInt998244353>::new(0);
+            for n in 0..30 {
+                for k in 0..=n + 1 {
+                    let expected = if k > n { 0 } else { binom_naive(n, k) };
+                    assert_eq!(
+                        ModInt998244353::new(expected),
+                        binomial.binom(n as _, k as _),
+                    );
+                }
+            }
+            for n in 1..1000 {
+                assert_eq!(ModInt998244353::new(n).inv(), binomial.inv(n));
+            }
+            let catalans = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862];
+            for (n, &c) in catalans.iter().enumerate() {
+                assert_eq!(ModInt998244353::new(c), binomial.catalan(n));
+            }
+            assert_eq!(
+                ModInt998244353::new(1),
+                Binomial::<ModInt998244353>::new(0).catalan(0)
+            );
+            assert_eq!(
+                ModInt998244353::new(1),
+                Binomial::<ModInt998244353>::new(0).catalan(1)
+            );
+            assert_eq!(ModInt998244353::new(1), binomial.multinomial(&[]));
+            assert_eq!(ModInt998244353::new(1260), binomial.multinomial(&[4, 3, 2]));
+            assert_eq!(ModInt998244353::new(0), binomial.perm(3, 4));
+            assert_eq!(ModInt998244353::new(60), binomial.perm(5, 3));
+
+            // the default modulus of `DynamicModInt` is 998244353
+            crate::define_id!(thread_local Id;);
+            let mut binomial = Binomial::<DynamicModInt<Id>>::new(100);
+            assert_eq!(DynamicModInt::<Id>::new(161_700), binomial.binom(100, 3));
+        }
+
+        #[test]
+        fn sqrt() {
+            use super::{ModIntBase, MontgomeryModInt, StaticModInt};
+
+            modulus!(M2(2, true), M3(3, true), M17(17, true), M10007(10007, true));
+
+            fn test<Z: ModIntBase>() {
+                let p = Z::modulus();
+                let mut squares = vec![None; p as usize];
+                for x in (0..p).rev() {
+                    let x = Z::raw(x);
+                    squares[(x * x).val() as usize] = Some(x);
+                }
+                for (a, &expected) in squares.iter().enumerate() {
+                    assert_eq!(expected, Z::new(a).sqrt());
+                }
+            }
+
+            test::<StaticModInt<M2>>();
+            test::<StaticModInt<M3>>();
+            test::<StaticModInt<M17>>();
+            test::<StaticModInt<M10007>>();
+            test::<MontgomeryModInt<M10007>>();
+
+            for &a in &[0u32, 1, 2, 4, 5, 123_456_789, 998_244_352] {
+                let a = super::ModInt998244353::new(a);
+                if let Some(r) = a.sqrt() {
+                    assert_eq!(a, r * r);
+                    assert!(r.val() <= (-r).val());
+                }
+            }
+            assert_eq!(None, super::ModInt998244353::new(3).sqrt());
+        }
+
+        #[test]
+        #[should_panic]
+        fn sqrt_with_composite_modulus() {
+            use super::StaticModInt;
+
+            modulus!(M9(9, false));
+
+            StaticModInt::<M9>::new(1).sqrt();
+        }
+
+        #[test]
+        fn discrete_log() {
+            use super::{ModIntBase, StaticModInt};
+
+            modulus!(
+                M2(2, true),
+                M12(12, false),
+                M64(64, false),
+                M101(101, true),
+                M360(360, false)
+            );
+
+            fn test<Z: ModIntBase>() {
+                let m = Z::modulus();
+                for a in 0..m {
+                    for b in 0..m {
+                        let (a, b) = (Z::raw(a), Z::raw(b));
+                        let expected = (0..2 * u64::from(m) + 2).find(|&k| a.pow(k) == b);
+                        assert_eq!(expected, super::discrete_log(a, b));
+                    }
+                }
+            }
+
+            test::<StaticModInt<M2>>();
+            test::<StaticModInt<M12>>();
+            test::<StaticModInt<M64>>();
+            test::<StaticModInt<M101>>();
+            test::<StaticModInt<M360>>();
+
+            let a = ModInt1000000007::new(5);
+            assert_eq!(Some(999_999), super::discrete_log(a, a.pow(999_999)));
+        }
+
+        #[test]
+        fn dynamic_modint_with_modulus() {
+            use super::DynamicModInt;
+            use std::panic;
+
+            crate::define_id!(static Id;);
+
+            type Z = DynamicModInt<Id>;
+
+            assert_eq!(998_244_353, Z::modulus());
+            let x = Z::with_modulus(7, || {
+                assert_eq!(7, Z::modulus());
+                Z::with_modulus(11, || assert_eq!(11, Z::modulus()));
+                assert_eq!(7, Z::modulus());
+                Z::new(10) * Z::new(10)
+            });
+            assert_eq!(2, x.val());
+            assert_eq!(998_244_353, Z::modulus());
+
+            let result = panic::catch_unwind(|| Z::with_modulus(13, || panic!()));
+            assert!(result.is_err());
+            assert_eq!(998_244_353, Z::modulus());
+        }
+
+        #[test]
+        fn dynamic_modint_thread_local_id() {
+            use super::DynamicModInt;
+            use std::thread;
+
+            crate::define_id!(thread_local Id;);
+
+            type Z = DynamicModInt<Id>;
+
+            let handles = (2..10)
+                .map(|m| {
+                    thread::spawn(move || {
+                        Z::set_modulus(m);
+                        thread::yield_now();
+                        (0..1000).all(|_| Z::modulus() == m && Z::new(m + 1).val() == 1)
+                    })
+                })
+                .collect::<Vec<_>>();
+            for handle in handles {
+                assert!(handle.join().unwrap());
+            }
+            assert_eq!(998_244_353, Z::modulus());
+        }
+
+        #[test]
+        fn slice_operations() {
+            use super::{DynamicModInt, ModInt998244353};
+
+            let f = ModInt1000000007::new;
+            let a = (1..=100).map(f).collect::<Vec<_>>();
+
+            let mut inv = a.clone();
+            super::batch_inv(&mut inv);
+            assert!(a.iter().zip(&inv).all(|(&x, &y)| x * y == f(1)));
+            super::batch_inv(&mut [] as &mut [ModInt1000000007]);
+
+            let prefix = super::prefix_products(&a);
+            assert_eq!(101, prefix.len());
+            assert_eq!(f(1), prefix[0]);
+            assert_eq!(a.iter().product::<ModInt1000000007>(), prefix[100]);
+
+            let mut b = a.clone();
+            super::pointwise_mul(&mut b, &a);
+            assert_eq!(a.iter().map(|&x| x * x).collect::<Vec<_>>(), b);
+            super::scale(&mut b, f(-1));
+            assert_eq!(a.iter().map(|&x| -x * x).collect::<Vec<_>>(), b);
+
+            crate::define_id!(thread_local Id;);
+            DynamicModInt::<Id>::set_modulus(10);
+            let mut a = [1, 3, 7, 9]
+                .iter()
+                .map(|&x| DynamicModInt::<Id>::new(x))
+                .collect::<Vec<_>>();
+            super::batch_inv(&mut a);
+            assert_eq!(
+                vec![1, 7, 3, 9],
+                a.iter().map(|x| x.val()).collect::<Vec<_>>()
+            );
+
+            let mut a = [ModInt998244353::new(2)];
+            super::batch_inv(&mut a);
+            assert_eq!(ModInt998244353::new(499_122_177), a[0]);
+        }
+
+        #[test]
+        #[should_panic]
+        fn batch_inv_of_zero() {
+            super::batch_inv(&mut [ModInt1000000007::new(1), ModInt1000000007::new(0)]);
+        }
+
+        #[cfg(const_fn_trait_bound)]
+        #[test]
+        fn const_construction() {
+            use super::ModInt998244353;
+
+            const A: ModInt1000000007 = ModInt1000000007::from_const_u32(1_000_000_008);
+            const B: ModInt1000000007 = ModInt1000000007::from_const_u32(3).const_pow(100);
+            const C: ModInt998244353 = ModInt998244353::from_const_u32(12345).const_inv();
+
+            assert_eq!(ModInt1000000007::new(1), A);
+            assert_eq!(ModInt1000000007::new(3).pow(100), B);
+            assert_eq!(ModInt998244353::new(12345).inv(), C);
+        }
+
+        #[cfg(const_fn_trait_bound)]
+        #[test]
+        #[should_panic]
+        fn const_inv_with_composite_modulus() {
+            modulus!(M9(9, false));
+            super::StaticModInt::<M9>::from_const_u32(2).const_inv();
+        }
+
+        #[cfg(const_fn_control_flow)]
+        #[test]
+        fn define_static_modulus() {
+            use super::{Modulus, StaticModInt};
+
+            define_static_modulus!(Mod7, 7);
+            define_static_modulus!(Mod9, 9);
+            define_static_modulus!(
+                /// The largest prime below 2^31.
+                Mod2147483647,
+                2_147_483_647,
+            );
+            define_static_modulus!(Mod4294967291, 4_294_967_291);
+
+            assert!(Mod7::HINT_VALUE_IS_PRIME);
+            assert!(!Mod9::HINT_VALUE_IS_PRIME);
+            assert!(Mod2147483647::HINT_VALUE_IS_PRIME);
+            assert!(Mod4294967291::HINT_VALUE_IS_PRIME);
+
+            assert_eq!(
+                StaticModInt::<Mod7>::new(4),
+                StaticModInt::<Mod7>::new(2).inv()
+            );
+            assert_eq!(
+                StaticModInt::<Mod9>::new(5),
+                StaticModInt::<Mod9>::new(2).inv()
+            );
+            assert_eq!(
+                StaticModInt::<Mod4294967291>::new(1),
+                StaticModInt::<Mod4294967291>::new(3) * StaticModInt::<Mod4294967291>::new(3).inv(),
+            );
+        }
+
+        #[test]
+        fn try_raw() {
+            use super::{DynamicModInt, ModInt2305843009213693951};
+
+            assert_eq!(Some(ModInt1000000007::raw(0)), ModInt1000000007::try_raw(0));
+            assert_eq!(
+                Some(ModInt1000000007::raw(1_000_000_006)),
+                ModInt1000000007::try_raw(1_000_000_006),
+            );
+            assert_eq!(None, ModInt1000000007::try_raw(1_000_000_007));
+            assert_eq!(None, ModInt1000000007::try_raw(u32::max_value()));
+
+            crate::define_id!(thread_local Id;);
+            DynamicModInt::<Id>::set_modulus(10);
+            assert_eq!(
+                Some(DynamicModInt::<Id>::raw(9)),
+                DynamicModInt::<Id>::try_raw(9),
+            );
+            assert_eq!(None, DynamicModInt::<Id>::try_raw(10));
+
+            assert_eq!(None, ModInt2305843009213693951::try_raw((1 << 61) - 1));
+        }
+
+        #[test]
+        fn checked_new() {
+            use super::{
+                Mod998244353, ModInt2305843009213693951, MontgomeryModInt, ParseModIntErrorKind,
+            };
+
+            assert_eq!(Ok(ModInt1000000007::new(0)), "0".parse());
+            assert_eq!(Ok(ModInt1000000007::new(42)), "+42".parse());
+            assert_eq!(Ok(ModInt1000000007::new(-42)), "-42".parse());
+            assert_eq!(Ok(ModInt1000000007::new(0)), "-0".parse());
+            assert_eq!(Ok(ModInt1000000007::new(0)), "1000000007".parse());
+            assert_eq!(
+                Ok(ModInt1000000007::new(10).pow(100)),
+                ModInt1000000007::checked_new(&format!("1{}", "0".repeat(100))),
+            );
+            assert_eq!(
+                Ok(-ModInt1000000007::new(10).pow(100)),
+                ModInt1000000007::checked_new(&format!("-1{}", "0".repeat(100))),
+            );
+            assert_eq!(
+                Ok(MontgomeryModInt::<Mod998244353>::new(10).pow(30)),
+                format!("1{}", "0".repeat(30)).parse(),
+            );
+            assert_eq!(
+                Ok(ModInt2305843009213693951::new(10).pow(40)),
+                format!("1{}", "0".repeat(40)).parse(),
+            );
+
+            for &(s, kind) in &[
+                ("", ParseModIntErrorKind::Empty),
+                ("-", ParseModIntErrorKind::InvalidDigit),
+                ("+", ParseModIntErrorKind::InvalidDigit),
+                ("--1", ParseModIntErrorKind::InvalidDigit),
+                (" 1", ParseModIntErrorKind::InvalidDigit),
+                ("1.0", ParseModIntErrorKind::InvalidDigit),
+                ("1e9", ParseModIntErrorKind::InvalidDigit),
+                ("１", ParseModIntErrorKind::InvalidDigit),
+            ] {
+                let err = ModInt1000000007::checked_new(s).unwrap_err();
+                assert_eq!(&kind, err.kind());
+                assert_eq!(err, ModInt2305843009213693951::checked_new(s).unwrap_err());
+            }
+            assert_eq!(
+                "invalid digit found in string",
+                ModInt1000000007::checked_new("x").unwrap_err().to_string(),
+            );
+        }
+
+        #[test]
+        fn montgomery_modint_large_odd_modulus() {
+            use super::{ButterflyCache, Modulus, MontgomeryModInt, StaticModInt};
+            use std::{cell::RefCell, thread::LocalKey};
+
+            // odd, close to 2^32 and not a prime
+            const M: u32 = 4_294_967_289;
+
+            #[derive(Copy, Clone, Eq, PartialEq)]
+            enum Mod {}
+
+            impl Modulus for Mod {
+                const VALUE: u32 = M;
+                const HINT_VALUE_IS_PRIME: bool = false;
+
+                fn butterfly_cache() -> &'static LocalKey<RefCell<Option<ButterflyCache<Self>>>> {
+                    thread_local! {
+                        static BUTTERFLY_CACHE: RefCell<Option<ButterflyCache<Mod>>> = RefCell::default();
+                    }
+                    &BUTTERFLY_CACHE
+                }
+            }
+
+            let values = [0, 1, 2, 3_000_000_000, M - 2, M - 1];
+            for &a in &values {
+                for &b in &values {
+                    let expected = StaticModInt::<Mod>::new(a) * StaticModInt::<Mod>::new(b);
+                    let actual = MontgomeryModInt::<Mod>::new(a) * MontgomeryModInt::<Mod>::new(b);
+                    assert_eq!(expected.val(), actual.val());
+                    assert_eq!(expected, actual.into());
+
+                    let (x, y, m) = (u64::from(a), u64::from(b), u64::from(M));
+                    let (z, w) = (
+                        MontgomeryModInt::<Mod>::new(a),
+                        MontgomeryModInt::<Mod>::new(b),
+                    );
+                    assert_eq!((x + y) % m, u64::from((z + w).val()));
+                    assert_eq!((m + x - y) % m, u64::from((z - w).val()));
+                    assert_eq!((m - x) % m, u64::from((-z).val()));
+                }
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde() {
+            use super::{
+                Barrett64, DynamicModInt, DynamicModInt64, Id64, Mod998244353,
+                ModInt2305843009213693951, ModInt998244353, MontgomeryModInt,
+            };
+
+            crate::define_id!(thread_local Id;);
+
+            #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+            enum LocalId64 {}
+
+            impl Id64 for LocalId64 {
+                fn companion_barrett() -> &'static Barrett64 {
+                    static BARRETT: Barrett64 = Barrett64::default();
+                    &BARRETT
+                }
+            }
+
+            let a = vec![
+                ModInt998244353::new(0),
+                ModInt998244353::new(1),
+                ModInt998244353::new(998_244_352),
+            ];
+            let json = serde_json::to_string(&a).unwrap();
+            assert_eq!("[0,1,998244352]", json);
+            assert_eq!(
+                a,
+                serde_json::from_str::<Vec<ModInt998244353>>(&json).unwrap()
+            );
+
+            assert_eq!(
+                ModInt998244353::new(998_244_354),
+                serde_json::from_str::<ModInt998244353>("998244354").unwrap(),
+            );
+            assert_eq!(
+                ModInt998244353::new(-1),
+                serde_json::from_str::<ModInt998244353>("-1").unwrap()
+            );
+            assert!(serde_json::from_str::<ModInt998244353>("1.5").is_err());
+            assert!(serde_json::from_str::<ModInt998244353>("\"1\"").is_err());
+
+            let a = MontgomeryModInt::<Mod998244353>::new(12345);
+            assert_eq!("12345", serde_json::to_string(&a).unwrap());
+            assert_eq!(a, serde_json::from_str("12345").unwrap());
+
+            DynamicModInt::<Id>::set_modulus(7);
+            assert_eq!(
+                "3",
+                serde_json::to_string(&DynamicModInt::<Id>::new(10)).unwrap()
+            );
+            assert_eq!(
+                DynamicModInt::<Id>::new(3),
+                serde_json::from_str("10").unwrap()
+            );
+
+            let a = ModInt2305843009213693951::new(2_305_843_009_213_693_950u64);
+            assert_eq!("2305843009213693950", serde_json::to_string(&a).unwrap());
+            assert_eq!(a, serde_json::from_str("-1").unwrap());
+            assert_eq!(
+                DynamicModInt64::<LocalId64>::new(1u64 << 63),
+                serde_json::from_str("9223372036854775808").unwrap(),
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_non_self_describing() {
+            use super::{
+                Barrett64, DynamicModInt, DynamicModInt64, Id64, Mod998244353,
+                ModInt2305843009213693951, ModInt998244353, MontgomeryModInt,
+            };
+            use serde::{de::DeserializeOwned, Serialize};
+            use std::fmt::Debug;
+
+            crate::define_id!(thread_local Id;);
+
+            #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+            enum LocalId64 {}
+
+            impl Id64 for LocalId64 {
+                fn companion_barrett() -> &'static Barrett64 {
+                    static BARRETT: Barrett64 = Barrett64::default();
+                    &BARRETT
+                }
+            }
+
+            fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(
+                a: Vec<T>,
+            ) -> Vec<u8> {
+                let bytes = bincode::serialize(&a).unwrap();
+                assert_eq!(a, bincode::deserialize::<Vec<T>>(&bytes).unwrap());
+                bytes
+            }
+
+            let bytes = round_trip(vec![ModInt998244353::new(5), ModInt998244353::new(7)]);
+            assert_eq!(vec![2, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 7, 0, 0, 0], bytes);
+            round_trip(vec![
+                MontgomeryModInt::<Mod998244353>::new(5),
+                MontgomeryModInt::<Mod998244353>::new(998_244_352),
+            ]);
+            round_trip(vec![
+                DynamicModInt::<Id>::new(5),
+                DynamicModInt::<Id>::new(7),
+            ]);
+            let bytes = round_trip(vec![ModInt2305843009213693951::new(5u64)]);
+            assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0], bytes);
+            round_trip(vec![DynamicModInt64::<LocalId64>::new(1u64 << 63)]);
+        }
     }
 }
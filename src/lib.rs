@@ -1,6 +1,9 @@
+pub extern crate __acl_bigint as bigint;
 pub extern crate __acl_convolution as convolution;
 pub extern crate __acl_dsu as dsu;
 pub extern crate __acl_fenwicktree as fenwicktree;
+pub extern crate __acl_fft as fft;
+pub extern crate __acl_fps as fps;
 pub extern crate __acl_lazysegtree as lazysegtree;
 pub extern crate __acl_math as math;
 pub extern crate __acl_maxflow as maxflow;
@@ -11,16 +14,35 @@ pub extern crate __acl_segtree as segtree;
 pub extern crate __acl_string as string;
 pub extern crate __acl_twosat as twosat;
 
-pub use convolution::{convolution, convolution_i64};
+pub use bigint::{BigInt, BigUint};
+pub use convolution::{
+    and_convolution, convolution, convolution_2d, convolution_any_mod, convolution_i64,
+    convolution_nd, convolution_truncated, cyclic_convolution, gcd_convolution, intt,
+    lcm_convolution, middle_product, ntt, or_convolution, subset_convolution, xor_convolution,
+    Convolver,
+};
 pub use dsu::Dsu;
 pub use fenwicktree::FenwickTree;
+pub use fft::{convolution_f64, convolution_u128};
+pub use fps::{
+    berlekamp_massey, evaluate_geometric, interpolate, kth_term_of_rational, multipoint_eval,
+    polynomial_taylor_shift, Fps,
+};
 pub use lazysegtree::{LazySegtree, MapMonoid};
-pub use math::{crt, floor_sum, inv_mod, pow_mod};
+pub use math::{
+    carmichael_lambda, crt, crt_checked, crt_u128, dirichlet_prefix_sum, divisors, euler_phi,
+    factorize, floor_monoid_product, floor_sum, floor_sum_squared, floor_sum_weighted, garner,
+    inv_mod, is_prime_u64, min_of_mod_linear, multiplicative_prefix_sum, pow_mod, prime_pi,
+    CrtError, CrtErrorKind, Sieve,
+};
 pub use maxflow::{Edge, MfGraph};
 pub use mincostflow::MinCostFlowGraph;
 pub use modint::{
-    Barrett, ButterflyCache, DefaultId, DynamicModInt, Id, Mod1000000007, Mod998244353, ModInt,
-    ModInt1000000007, ModInt998244353, Modulus, RemEuclidU32, StaticModInt,
+    Barrett, Barrett64, Binomial, ButterflyCache, DefaultId, DefaultId64, DynamicModInt,
+    DynamicModInt64, Id, Id64, Mod1000000007, Mod2305843009213693951, Mod998244353, ModInt,
+    ModInt1000000007, ModInt2305843009213693951, ModInt64, ModInt998244353, Modulus, Modulus64,
+    MontgomeryModInt, ParseModIntError, ParseModIntErrorKind, RemEuclidU32, RemEuclidU64,
+    StaticModInt, StaticModInt64,
 };
 pub use scc::SccGraph;
 pub use segtree::{Additive, Max, Min, Monoid, Multiplicative, Segtree};
@@ -10,7 +10,13 @@ use std::{
     path::{self, PathBuf},
 };
 use structopt::StructOpt;
-use syn::{spanned::Spanned as _, visit::Visit, Item, Lit, Meta, MetaNameValue, Visibility};
+use syn::{spanned::Spanned as _, visit::Visit, Lit, Meta, MetaNameValue, Visibility};
+
+/// Modules that have been extended beyond ac-library-rs.
+///
+/// Expanding them again would discard the extensions, so they are maintained by hand and changes in
+/// ac-library-rs have to be ported to them manually.
+const EXTENDED_MODULES: &[&str] = &["convolution", "internal_math", "math", "modint"];
 
 #[derive(StructOpt)]
 struct Opt {}
@@ -48,6 +54,12 @@ fn main() -> anyhow::Result<()> {
         let ac_library_rs_parted_x = metadata_for_ac_library_rs_parted.workspace_member(
             &format!("ac-library-rs-parted-{}", module_name.replace('_', "-")),
         )?;
+        let cm::Target { src_path, .. } = ac_library_rs_parted_x.lib_target()?;
+
+        if EXTENDED_MODULES.contains(module_name) {
+            eprintln!("     Skipped {} (extended by hand)", src_path.display());
+            continue;
+        }
 
         let extern_crate_names = metadata_for_ac_library_rs_parted
             .extern_crate_names_for_normal_dependencies(&ac_library_rs_parted_x.id);
@@ -90,18 +102,14 @@ fn main() -> anyhow::Result<()> {
                 .join(""),
         );
 
-        let cm::Target { src_path, .. } = ac_library_rs_parted_x.lib_target()?;
         write_file(src_path, code)?;
         rustfmt(src_path)?;
         eprintln!("    Modified {}", src_path.display());
     }
 
-    let cm::Target { src_path, .. } = ac_library_rs;
-    let code = modify_top(&read_file(src_path)?)?;
+    // `src/lib.rs` also re-exports the crates that are not in ac-library-rs, so it is maintained by hand too.
     let cm::Target { src_path, .. } = ac_library_rs_parted.lib_target()?;
-    write_file(src_path, code)?;
-    rustfmt(src_path)?;
-    eprintln!("    Modified {}", src_path.display());
+    eprintln!("     Skipped {} (extended by hand)", src_path.display());
 
     Ok(())
 }
@@ -157,32 +165,6 @@ fn replace_vis_stricts(code: &str) -> syn::Result<String> {
     }
 }
 
-fn modify_top(code: &str) -> syn::Result<String> {
-    let syn::File { items, .. } = syn::parse_file(code)?;
-
-    let replace_with = items
-        .iter()
-        .flat_map(|item| match item {
-            Item::Mod(item_mod) => Some(item_mod),
-            _ => None,
-        })
-        .map(|item_mod| {
-            let span = item_mod.span();
-            let replace_with = if let Visibility::Public(_) = item_mod.vis {
-                format!(
-                    "pub extern crate __acl_{ident} as {ident};",
-                    ident = item_mod.ident
-                )
-            } else {
-                "".to_owned()
-            };
-            (span, replace_with)
-        })
-        .collect::<Vec<_>>();
-
-    Ok(replace_ranges(code, &replace_with))
-}
-
 fn replace_ranges(code: &str, with: &[(Span, String)]) -> String {
     let to_range = {
         let lines = code.split('\n').collect::<Vec<_>>();